@@ -0,0 +1,21 @@
+// cargo bench --bench sentiment
+//
+// scoring used to parse the whole AFINN list for every line the player typed,
+// `parse lexicon` is that old per-utterance cost for comparison
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use code::sentiment::{afinn, score_utterance};
+
+const LINE: &str = "Hey there neighbor, I'm really sorry about the noise last night, it won't happen again!";
+
+fn sentiment(c: &mut Criterion) {
+    // make sure the first timed iteration isn't the one that parses the lexicon
+    afinn::lexicon();
+
+    c.bench_function("score_utterance", |b| b.iter(|| score_utterance(black_box(LINE))));
+    c.bench_function("parse lexicon", |b| b.iter(afinn::parse_lexicon));
+}
+
+criterion_group!(benches, sentiment);
+criterion_main!(benches);
@@ -0,0 +1,130 @@
+// trains the naive bayes scorer on the bundled corpus and checks how well it does on lines it never saw
+//
+//   cargo run --bin classifier -- train [--corpus <file>] [--out <file>]
+//   cargo run --bin classifier -- eval [--model <file>] [--heldout <file>] [--blend <n>] [--misses]
+//
+// the corpus and the held out lines are "label<tab>line" with neighborly or hostile as the label,
+// by default everything is read from and written to this crate's assets folder
+//
+// options:
+//   --corpus <file>    lines to train on, defaults to assets/classifier/corpus.tsv
+//   --out <file>       where the trained model goes, defaults to assets/classifier.json
+//   --model <file>     the model to evaluate, defaults to assets/classifier.json
+//   --heldout <file>   lines to evaluate on, defaults to assets/classifier/heldout.tsv
+//   --blend <n>        how much of the blended score is the classifier's, defaults to the one in scoring.json
+//   --misses           print every line a scorer got wrong
+
+use std::path::{Path, PathBuf};
+use std::process;
+use std::{env, fs};
+
+use code::sentiment::classifier::{parse_corpus, Example, Label, NaiveBayes, ScorerConfig};
+use code::sentiment::lexicon::{LexiconConfig, LexiconSet};
+use code::sentiment::score_utterance_with;
+
+// one way of scoring a line, the sign is what it thinks the line is
+type LineScorer<'a> = Box<dyn Fn(&str) -> f64 + 'a>;
+
+const USAGE: &str = "usage: classifier train [--corpus <file>] [--out <file>]\n       classifier eval [--model <file>] [--heldout <file>] [--blend <n>] [--misses]";
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let command = args.next().unwrap_or_default();
+    let result = match command.as_str() {
+        "train" => train(args),
+        "eval" => eval(args),
+        _ => Err(String::from("expected train or eval")),
+    };
+    if let Err(e) = result {
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    }
+}
+
+fn asset_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("assets")
+}
+
+fn read_examples(path: &Path) -> Result<Vec<Example>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    parse_corpus(&text).map_err(|line| format!("line {} of {} is not \"neighborly|hostile<tab>line\"", line, path.display()))
+}
+
+fn train(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut corpus = asset_dir().join("classifier").join("corpus.tsv");
+    let mut out = asset_dir().join("classifier.json");
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--corpus" => corpus = PathBuf::from(value("--corpus")?),
+            "--out" => out = PathBuf::from(value("--out")?),
+            _ => return Err(format!("unknown option {}", arg)),
+        }
+    }
+    let examples = read_examples(&corpus)?;
+    let neighborly = examples.iter().filter(|example| example.label == Label::Neighborly).count();
+    let model = NaiveBayes::train(&examples);
+    model.save(&out).map_err(|e| format!("could not write {}: {}", out.display(), e))?;
+    println!("trained on {} lines ({} neighborly, {} hostile), wrote {}",
+        examples.len(), neighborly, examples.len() - neighborly, out.display());
+    Ok(())
+}
+
+fn eval(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut model_path = asset_dir().join("classifier.json");
+    let mut heldout = asset_dir().join("classifier").join("heldout.tsv");
+    let mut blend = None;
+    let mut misses = false;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--model" => model_path = PathBuf::from(value("--model")?),
+            "--heldout" => heldout = PathBuf::from(value("--heldout")?),
+            "--blend" => {
+                let text = value("--blend")?;
+                blend = Some(text.parse::<f64>().map_err(|_| format!("\"{}\" is not a number", text))?);
+            }
+            "--misses" => misses = true,
+            _ => return Err(format!("unknown option {}", arg)),
+        }
+    }
+    let scoring = asset_dir().join("scoring.json");
+    let json = fs::read_to_string(&scoring).map_err(|e| format!("could not read {}: {}", scoring.display(), e))?;
+    let mut config: LexiconConfig = serde_json::from_str(&json).map_err(|e| format!("{}: {}", scoring.display(), e))?;
+    let blend = blend.unwrap_or(config.scorer.blend);
+    // the game's lexicons on their own, the model being evaluated might not be the one scoring.json points at
+    config.scorer = ScorerConfig::default();
+    let lexicons = LexiconSet::from_config(&config, &asset_dir()).map_err(|e| e.to_string())?;
+    let lexicon = lexicons.for_neighbor("");
+    let model = NaiveBayes::load(&model_path).map_err(|e| e.to_string())?;
+    let examples = read_examples(&heldout)?;
+
+    let lexicon_score = |text: &str| score_utterance_with(text, &lexicon).score;
+    let scorers: [(&str, LineScorer); 3] = [
+        ("afinn", Box::new(lexicon_score)),
+        ("classifier", Box::new(|text| model.score(text))),
+        ("blend", Box::new(|text| blend * model.score(text) + (1. - blend) * lexicon_score(text))),
+    ];
+    println!("{} held out lines from {}", examples.len(), heldout.display());
+    for (name, score) in &scorers {
+        let mut correct = 0;
+        let mut missed = Vec::new();
+        for example in &examples {
+            let score = score(&example.text);
+            // a line that scores nothing isn't neighborly or hostile, so it counts as a miss
+            let guess = if score > 0. { Some(Label::Neighborly) } else if score < 0. { Some(Label::Hostile) } else { None };
+            if guess == Some(example.label) {
+                correct += 1;
+            } else {
+                missed.push((example, score));
+            }
+        }
+        println!("{:>10}: {}/{} right, {:.1}%", name, correct, examples.len(), 100. * correct as f64 / examples.len() as f64);
+        if misses {
+            for (example, score) in missed {
+                println!("            {:?} {:+.1}  {}", example.label, score, example.text);
+            }
+        }
+    }
+    Ok(())
+}
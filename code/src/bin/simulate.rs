@@ -0,0 +1,229 @@
+// plays a scripted conversation against one neighbor without opening the game,
+// so dialogue balance can be checked from the command line
+//
+//   cargo run --bin simulate -- <neighbor id> <script file, or - for stdin> [options]
+//
+// the script has one player line per line, blank lines and lines starting with # are skipped
+//
+// options:
+//   --tolerance <n>    start with this tolerance instead of the neighbor's own
+//   --seed <n>         seed for random tolerances and reply picks, so runs can be repeated
+//   --assets <dir>     where neighbors.json lives, defaults to this crate's assets folder
+//   --scoring <file>   which lexicons to use, defaults to scoring.json in the assets folder
+//   --expect <result>  won, lost or ongoing, exits with an error if the conversation ends differently
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::{env, fs};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use code::roster::NeighborRoster;
+use code::scoring;
+use code::sentiment::lexicon::LexiconSet;
+use code::session::{ConversationSession, Speaker, Verdict, MAX_TURNS};
+
+const USAGE: &str = "usage: simulate <neighbor id> <script|-> [--tolerance <n>] [--seed <n>] [--assets <dir>] [--scoring <file>] [--expect won|lost|ongoing]";
+
+struct Options {
+    neighbor: String,
+    script: String,
+    tolerance: Option<f64>,
+    seed: Option<u64>,
+    assets: PathBuf,
+    scoring: Option<PathBuf>,
+    expect: Option<Verdict>,
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    match run(&options) {
+        Ok(verdict) => {
+            if let Some(expected) = options.expect {
+                if verdict != expected {
+                    eprintln!("expected the conversation to be {} but it was {}", verdict_name(expected), verdict_name(verdict));
+                    process::exit(1);
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut positional = Vec::new();
+    let mut options = Options {
+        neighbor: String::new(),
+        script: String::new(),
+        tolerance: None,
+        seed: None,
+        assets: Path::new(env!("CARGO_MANIFEST_DIR")).join("assets"),
+        scoring: None,
+        expect: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--tolerance" => options.tolerance = Some(parse_number(&value("--tolerance")?)?),
+            "--seed" => options.seed = Some(parse_number(&value("--seed")?)?),
+            "--assets" => options.assets = PathBuf::from(value("--assets")?),
+            "--scoring" => options.scoring = Some(PathBuf::from(value("--scoring")?)),
+            "--expect" => options.expect = Some(match value("--expect")?.as_str() {
+                "won" => Verdict::Won,
+                "lost" => Verdict::Lost,
+                "ongoing" => Verdict::Ongoing,
+                other => return Err(format!("unknown result \"{}\"", other)),
+            }),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+    if positional.len() != 2 {
+        return Err(String::from("expected a neighbor id and a script"));
+    }
+    options.script = positional.pop().unwrap();
+    options.neighbor = positional.pop().unwrap();
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("\"{}\" is not a number", value))
+}
+
+fn read_script(script: &str) -> Result<Vec<String>, String> {
+    let mut text = String::new();
+    if script == "-" {
+        io::stdin().read_to_string(&mut text).map_err(|e| format!("could not read stdin: {}", e))?;
+    } else {
+        text = fs::read_to_string(script).map_err(|e| format!("could not read {}: {}", script, e))?;
+    }
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+fn run(options: &Options) -> Result<Verdict, String> {
+    let roster = NeighborRoster::load(&options.assets.join("neighbors.json"), &options.assets)
+        .map_err(|e| e.to_string())?;
+    let neighbor = roster.get(&options.neighbor)
+        .ok_or(format!("there is no neighbor called \"{}\"", options.neighbor))?;
+    let scoring = options.scoring.clone().unwrap_or_else(|| options.assets.join("scoring.json"));
+    let lexicons = LexiconSet::load(&scoring, &options.assets, &roster).map_err(|e| e.to_string())?;
+    let lexicon = lexicons.for_neighbor(&neighbor.id);
+    let lines = read_script(&options.script)?;
+
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let tolerance = options.tolerance.unwrap_or_else(|| neighbor.tolerance.roll(&mut rng));
+    let mut session = ConversationSession::new(tolerance, MAX_TURNS);
+    session.record(Speaker::Neighbor, &neighbor.opening_line);
+
+    println!("{} ({}), starting tolerance {:.1}", neighbor.name, neighbor.id, tolerance);
+    println!("  {}: {}", neighbor.name, neighbor.opening_line);
+
+    let mut verdict = Verdict::Ongoing;
+    let mut turns = 0;  // lines that used up a turn, the session stops counting after the last turn
+    for (index, line) in lines.iter().enumerate() {
+        let response = scoring::respond(line, neighbor, &lexicon, lexicons.scorer(), &mut session, &mut rng);
+        let (scored, turn) = (&response.line, response.turn);
+        if scored.nonsense.is_none() || neighbor.confusion.costs_turn {
+            turns += 1;
+        }
+        let breakdown = &scored.sentiment;
+        let reply = &neighbor.replies(turn.reply)[response.reply];
+
+        println!("turn {}", index + 1);
+        println!("  you: {}", line);
+        if breakdown.heard != line.to_lowercase() {
+            println!("  heard: {}", breakdown.heard);
+        }
+        if let Some(nonsense) = scored.nonsense {
+            let turn_note = if neighbor.confusion.costs_turn { "" } else { ", turn not used" };
+            println!("  not understood ({:?}){}", nonsense, turn_note);
+        }
+        println!("  score {:+.1} (x{}), tolerance {:.1}", turn.score, breakdown.multiplier, turn.tolerance);
+        for sentence in &breakdown.sentences {
+            println!("    {:+.1} x{}  {}", sentence.score, sentence.weight, sentence.text);
+        }
+        if let Some(classified) = breakdown.classified {
+            println!("  classifier {:+.1}", classified);
+        }
+        match &scored.relevance {
+            Some(relevance) if relevance.mentioned.is_empty() =>
+                println!("  off topic for {} lines: {:+.1}", relevance.off_topic_streak, relevance.score),
+            Some(relevance) => println!("  on topic ({}): {:+.1}", relevance.mentioned.join(", "), relevance.score),
+            None => (),
+        }
+        if scored.repetition.times > 0 {
+            println!("  said before {} times: x{}", scored.repetition.times, scored.repetition.factor);
+        }
+        let mood = scored.mood.felt();
+        if !mood.is_empty() {
+            let mood: Vec<String> = mood.iter().map(|(emotion, value)| format!("{:?} {}", emotion, value)).collect();
+            println!("  mood: {} ({:+.1})", mood.join(", "), scored.mood_score);
+        }
+        if !scored.politeness.is_empty() {
+            let manners: Vec<String> = scored.politeness.iter().map(|(marker, bonus)| format!("{:?} {:+}", marker, bonus)).collect();
+            println!("  manners: {}", manners.join(", "));
+        }
+        if !scored.intents.is_empty() {
+            let intents: Vec<String> = scored.intents.iter().map(|(intent, weight)| format!("{:?} {:+}", intent, weight)).collect();
+            println!("  intents: {}", intents.join(", "));
+        }
+        if !breakdown.matched.is_empty() {
+            let matched: Vec<String> = breakdown.matched.iter().map(|(word, value)| format!("{} {:+}", word, value)).collect();
+            println!("  matched: {}", matched.join(", "));
+        }
+        if !breakdown.corrected.is_empty() {
+            let corrected: Vec<String> = breakdown.corrected.iter().map(|(typed, word)| format!("{} -> {}", typed, word)).collect();
+            println!("  typos: {}", corrected.join(", "));
+        }
+        if !breakdown.rules.is_empty() {
+            let rules: Vec<String> = breakdown.rules.iter()
+                .map(|effect| match &effect.word {
+                    Some(word) => format!("{:?} x{} on {}", effect.rule, effect.factor, word),
+                    None => format!("{:?} x{}", effect.rule, effect.factor),
+                })
+                .collect();
+            println!("  rules: {}", rules.join(", "));
+        }
+        if !breakdown.unknown.is_empty() {
+            println!("  unknown: {}", breakdown.unknown.join(", "));
+        }
+        println!("  {} ({}): {}", neighbor.name, format!("{:?}", turn.reply).to_lowercase(), reply);
+
+        verdict = turn.verdict;
+        if verdict != Verdict::Ongoing {
+            if index + 1 < lines.len() {
+                println!("({} more lines in the script were not used)", lines.len() - index - 1);
+            }
+            break;
+        }
+    }
+    println!("outcome: {} after {} turns", verdict_name(verdict), turns);
+    Ok(verdict)
+}
+
+fn verdict_name(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Ongoing => "ongoing",
+        Verdict::Won => "won",
+        Verdict::Lost => "lost",
+    }
+}
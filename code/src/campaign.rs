@@ -0,0 +1,171 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fighters::FighterRoster;
+use crate::roster::NeighborRoster;
+
+// names one encounter, the same id is used for the neighbor and their fighter profile
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct LevelId(pub String);
+
+impl LevelId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for LevelId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CampaignFile {
+    levels: Vec<LevelId>,
+}
+
+#[derive(Debug)]
+pub enum CampaignError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, source: serde_json::Error },
+    Empty,
+    UnknownNeighbor(LevelId),
+    UnknownFighter(LevelId),
+}
+
+impl fmt::Display for CampaignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CampaignError::Io { path, source } =>
+                write!(f, "could not read {}: {}", path.display(), source),
+            CampaignError::Parse { path, source } =>
+                write!(f, "{} is not a valid campaign: {}", path.display(), source),
+            CampaignError::Empty =>
+                write!(f, "the campaign has no levels"),
+            CampaignError::UnknownNeighbor(id) =>
+                write!(f, "level \"{}\" has no neighbor in the roster", id),
+            CampaignError::UnknownFighter(id) =>
+                write!(f, "level \"{}\" has no fighter profile", id),
+        }
+    }
+}
+
+impl std::error::Error for CampaignError {}
+
+// the ordered list of encounters and how far the player has gotten through it
+pub struct Campaign {
+    levels: Vec<LevelId>,
+    current: usize,
+}
+
+impl Campaign {
+    pub fn new(levels: Vec<LevelId>) -> Result<Self, CampaignError> {
+        if levels.is_empty() {
+            return Err(CampaignError::Empty);
+        }
+        Ok(Campaign { levels, current: 0 })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CampaignError> {
+        let json = fs::read_to_string(path).map_err(|source| CampaignError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let file: CampaignFile = serde_json::from_str(&json).map_err(|source| CampaignError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::new(file.levels)
+    }
+
+    // every level needs someone to talk to and someone to fight
+    pub fn validate(&self, roster: &NeighborRoster, fighters: &FighterRoster) -> Result<(), CampaignError> {
+        for level in &self.levels {
+            if roster.get(level.as_str()).is_none() {
+                return Err(CampaignError::UnknownNeighbor(level.clone()));
+            }
+            if fighters.get(level.as_str()).is_none() {
+                return Err(CampaignError::UnknownFighter(level.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn current(&self) -> &LevelId {
+        &self.levels[self.current]
+    }
+
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    // always false, a campaign can't be made without levels
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    // moves on to the next level, returns false when the last level was just beaten
+    pub fn advance(&mut self) -> bool {
+        if self.current + 1 < self.levels.len() {
+            self.current += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.current = 0;
+    }
+
+    // picks up from a saved level, returns false if the campaign no longer has it
+    pub fn jump_to(&mut self, level: &LevelId) -> bool {
+        match self.levels.iter().position(|id| id == level) {
+            Some(index) => {
+                self.current = index;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(id: &str) -> LevelId {
+        LevelId(String::from(id))
+    }
+
+    #[test]
+    fn advancing_past_the_last_level_finishes() {
+        let mut campaign = Campaign::new(vec![level("cathy"), level("billy")]).unwrap();
+        assert_eq!(campaign.current(), &level("cathy"));
+        assert!(campaign.advance());
+        assert_eq!(campaign.current(), &level("billy"));
+        assert!(!campaign.advance());
+        campaign.restart();
+        assert_eq!(campaign.current(), &level("cathy"));
+    }
+
+    #[test]
+    fn shipped_campaign_is_valid() {
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let roster = NeighborRoster::load(&asset_dir.join("neighbors.json"), &asset_dir).unwrap();
+        let fighters = FighterRoster::load_dir(&asset_dir.join("fighters"), &asset_dir).unwrap();
+        let campaign = Campaign::load(&asset_dir.join("campaign.json")).unwrap();
+        campaign.validate(&roster, &fighters).unwrap();
+        assert_eq!(campaign.len(), 10);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::sentiment::{is_negator, simplify, SentimentBreakdown};
+
+// hand picked for the game, every word has how strongly it carries its emotion from 1 to 3
+const EMOTIONS: &[u8] = include_bytes!("./sentiment/emotions.json");
+static LEXICON: OnceLock<Vec<(Vec<String>, Emotion, f64)>> = OnceLock::new();
+// a negator this many words before an emotion word cancels it, "not scared" isn't fear
+const NEGATION_WINDOW: usize = 2;
+// how much of the neighbor's mood carries over to the next line
+pub const MOOD_DECAY: f64 = 0.5;
+
+// what a line makes a neighbor feel, on top of how nice it is
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Emotion {
+    Anger,
+    Joy,
+    Fear,
+    Trust,
+    Calm,   // low key, unhurried language
+}
+
+pub const ALL: [Emotion; 5] = [Emotion::Anger, Emotion::Joy, Emotion::Fear, Emotion::Trust, Emotion::Calm];
+
+// how strongly a line, or a neighbor, leans toward each emotion
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Mood {
+    pub anger: f64,
+    pub joy: f64,
+    pub fear: f64,
+    pub trust: f64,
+    pub calm: f64,
+}
+
+impl Mood {
+    pub fn get(&self, emotion: Emotion) -> f64 {
+        match emotion {
+            Emotion::Anger => self.anger,
+            Emotion::Joy => self.joy,
+            Emotion::Fear => self.fear,
+            Emotion::Trust => self.trust,
+            Emotion::Calm => self.calm,
+        }
+    }
+
+    fn get_mut(&mut self, emotion: Emotion) -> &mut f64 {
+        match emotion {
+            Emotion::Anger => &mut self.anger,
+            Emotion::Joy => &mut self.joy,
+            Emotion::Fear => &mut self.fear,
+            Emotion::Trust => &mut self.trust,
+            Emotion::Calm => &mut self.calm,
+        }
+    }
+
+    // the emotions that are there at all, strongest first
+    pub fn felt(&self) -> Vec<(Emotion, f64)> {
+        let mut felt: Vec<(Emotion, f64)> = ALL.iter()
+            .map(|emotion| (*emotion, self.get(*emotion)))
+            .filter(|(_, value)| *value > 0.)
+            .collect();
+        felt.sort_by(|a, b| b.1.total_cmp(&a.1));
+        felt
+    }
+
+    // the mood after `line`, with what was felt before fading by `decay`
+    pub fn then(&self, line: &Mood, decay: f64) -> Mood {
+        let mut mood = Mood::default();
+        for emotion in ALL {
+            *mood.get_mut(emotion) = self.get(emotion) * decay + line.get(emotion);
+        }
+        mood
+    }
+}
+
+// what a neighbor thinks of each emotion when it isn't in their "emotions" in assets/neighbors.json
+pub fn default_weight(emotion: Emotion) -> f64 {
+    match emotion {
+        Emotion::Anger => -0.5,
+        Emotion::Joy => 0.25,
+        Emotion::Fear => -0.25,
+        Emotion::Trust => 0.25,
+        Emotion::Calm => 0.,
+    }
+}
+
+fn lexicon() -> &'static [(Vec<String>, Emotion, f64)] {
+    LEXICON.get_or_init(|| {
+        let categories: HashMap<Emotion, HashMap<String, f64>> =
+            serde_json::from_slice(EMOTIONS).expect("the bundled emotions.json is valid");
+        // "relax" and "relaxed" simplify to the same word, which should only count once
+        let mut simplified: HashMap<(Vec<String>, Emotion), f64> = HashMap::new();
+        for (emotion, words) in categories {
+            for (word, value) in words {
+                let words = simplify(&word);
+                if !words.is_empty() {
+                    let entry = simplified.entry((words, emotion)).or_insert(value);
+                    *entry = entry.max(value);
+                }
+            }
+        }
+        simplified.into_iter().map(|((words, emotion), value)| (words, emotion, value)).collect()
+    })
+}
+
+// true if `word`, already simplified, is one of the emotion words on its own
+pub fn is_emotion_word(word: &str) -> bool {
+    lexicon().iter().any(|(phrase, _, _)| phrase.len() == 1 && phrase[0] == word)
+}
+
+// every emotion word and phrase in the line added up, negated ones are left out
+pub fn feel(breakdown: &SentimentBreakdown) -> Mood {
+    let words = &breakdown.words;
+    let mut mood = Mood::default();
+    for (phrase, emotion, value) in lexicon() {
+        for start in 0..words.len() {
+            if words[start..].starts_with(phrase)
+                && !words[start.saturating_sub(NEGATION_WINDOW)..start].iter().any(|word| is_negator(word))
+            {
+                *mood.get_mut(*emotion) += value;
+            }
+        }
+    }
+    mood
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::score_utterance;
+
+    #[test]
+    fn lines_carry_emotions_and_moods_fade() {
+        let mood = feel(&score_utterance("I'm so angry, I hate this"));
+        assert_eq!(mood.felt(), vec![(Emotion::Anger, 6.)]);
+        assert_eq!(feel(&score_utterance("I'm not angry")), Mood::default());
+        let mood = feel(&score_utterance("relax, I promise it's safe"));
+        assert_eq!(mood.felt(), vec![(Emotion::Trust, 5.), (Emotion::Calm, 3.)]);
+
+        let later = mood.then(&feel(&score_utterance("haha")), MOOD_DECAY);
+        assert_eq!((later.trust, later.calm, later.joy), (2.5, 1.5, 2.));
+    }
+}
@@ -1,5 +1,3 @@
-use rand::Rng;
-use rand::seq::IteratorRandom;
 use bevy::{
     prelude::*
 };
@@ -8,7 +6,8 @@ use bevy::sprite::collide_aabb::Collision;
 use super::CollideEvent;
 use super::FightWinEvent;
 use super::FightLossEvent;
-use super::Level;
+use code::campaign::Campaign;
+use code::fighters::{EnemyAction, FighterProfile, FighterRoster};
 
 const PLAYER_W: f32 = 64.;
 const PLAYER_H: f32 = 128.;
@@ -86,6 +85,10 @@ impl Actions {
 #[derive(Component)]
 pub struct ActionTimer(Timer);
 
+// the profile from assets/fighters the enemy was spawned from
+#[derive(Component)]
+pub struct EnemyProfile(FighterProfile);
+
 #[derive(Component)]
 pub struct HealthBarTop;
 #[derive(Component)]
@@ -176,11 +179,26 @@ impl From<StateMachine<Move>> for StateMachine<Stand> {
 
 //====================FIGHT SETUP/OVERHEAD FUNCTIONS=======================
 
+const FIGHTERS_PATH: &str = "fighters";
+
+// reads every fighter profile out of the assets folder when the game starts
+pub fn load_fighters(mut commands: Commands) {
+    let asset_dir = crate::asset_dir();
+    match FighterRoster::load_dir(&asset_dir.join(FIGHTERS_PATH), &asset_dir) {
+        Ok(fighters) => {
+            info!("Loaded {} fighter profiles", fighters.len());
+            commands.insert_resource(fighters);
+        }
+        Err(e) => panic!("Could not load the fighter profiles: {}", e),
+    }
+}
+
 pub fn setup_fight(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    level: ResMut<State<Level>>,
+    campaign: Res<Campaign>,
+    fighters: Res<FighterRoster>,
 ) {
     let texture_handle = asset_server.load("start_sprite_screen.png");
     let texture_atlas = TextureAtlas::from_grid(texture_handle, Vec2::new(320., 180.), 46, 1);
@@ -212,189 +230,33 @@ pub fn setup_fight(
     .insert(Player);
 
 
-    match level.current(){
-        Level::Level1 => {
-            commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::ORANGE_RED,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(2., false)))    // enemy can perform one attack or block every 2 secs
-            .insert(Enemy);
-        } Level::Level2 => {
-            commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::TOMATO,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(2., false)))    // enemy can perform one attack or block every 2 secs
-            .insert(Enemy);
-        } Level::Level3 => {
-            commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::ANTIQUE_WHITE,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(2., false)))    // enemy can perform one attack or block every 2 secs
-            .insert(Enemy);
-        } Level::Level4 => {
-            commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::SALMON,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(2., false)))    // enemy can perform one attack or block every 2 secs
-            .insert(Enemy);
-        } Level::Level5 => {
-            commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::PINK,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(2., false)))    // enemy can perform one attack or block every 2 secs
-            .insert(Enemy);
-        } Level::Level6 => {
-			commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::ORANGE_RED,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(1.5, false)))    // enemy can perform one attack or block every 1.5 secs
-            .insert(Enemy);
-		} Level::Level7 => {
-			commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::TOMATO,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(1.5, false)))    // enemy can perform one attack or block every 1.5 secs
-            .insert(Enemy);
-		} Level::Level8 => {
-			commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::ANTIQUE_WHITE,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(1.5, false)))    // enemy can perform one attack or block every 1.5 secs
-            .insert(Enemy);
-		} Level::Level9 => {
-			commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::SALMON,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(1.5, false)))    // enemy can perform one attack or block every 1.5 secs
-            .insert(Enemy);
-		} Level::Level10 => {
-			commands.spawn_bundle(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::PINK,
-                    custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(crate::WIN_W/4., 0., 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Velocity::new())
-            .insert(Stats::new())
-            .insert(Actions::new())
-            .insert(ActionTimer(Timer::from_seconds(1.25, false)))    // enemy can perform one attack or block every 1.25 secs
-            .insert(Enemy);
-		}
+    // spawn the enemy for this level from its fighter profile
+    let profile = fighters.get(campaign.current().as_str())
+        .expect("every level should have a fighter profile")
+        .clone();
+    let [r, g, b] = profile.color;
+    let mut enemy_sprite = SpriteBundle {
+        sprite: Sprite {
+            color: Color::rgb(r, g, b),
+            custom_size: Some(Vec2::new(PLAYER_W, PLAYER_H)),
+            ..default()
+        },
+        transform: Transform {
+            translation: Vec3::new(crate::WIN_W/4., 0., 1.),
+            ..default()
+        },
+        ..default()
+    };
+    if let Some(sprite) = &profile.sprite {
+        enemy_sprite.texture = asset_server.load(sprite.as_str());
     }
+    commands.spawn_bundle(enemy_sprite)
+    .insert(Velocity::new())
+    .insert(Stats::new())
+    .insert(Actions::new())
+    .insert(ActionTimer(Timer::from_seconds(profile.cadence.opening, false)))    // enemy can perform one attack or block per cadence
+    .insert(EnemyProfile(profile))
+    .insert(Enemy);
     // spawn a dummy enemy sprite
     /* 
     commands.spawn_bundle(SpriteBundle {
@@ -712,10 +574,15 @@ pub fn collision_handle(
 	mut win_state: EventWriter<FightWinEvent>,
 	mut loss_state: EventWriter<FightLossEvent>,
 	mut player: Query<(&mut Transform, &mut Velocity, &mut Stats, &mut Actions), With<Player>>,
-	mut enemy: Query<(&mut Transform, &mut Velocity, &mut Stats, &mut Actions), (With<Enemy>, Without<Player>)>
+	mut enemy: Query<(&mut Transform, &mut Velocity, &mut Stats, &mut Actions), (With<Enemy>, Without<Player>)>,
+	enemy_profile: Query<&EnemyProfile, With<Enemy>>,
 ){
 	let (mut player_transform, mut player_velocity, mut player_stats, player_actions) = player.single_mut();
 	let (enemy_transform, mut enemy_velocity, mut enemy_stats, enemy_actions) = enemy.single_mut();
+	// the enemy's attacks are scaled by its fighter profile
+	let damage = &enemy_profile.single().0.damage_multipliers;
+	let punch_damage = PUNCHATTACK * damage.punch;
+	let kick_damage = KICKATTACK * damage.kick;
 	for p in event_receive.iter(){
 		if p.0 == true {
 			if p.1 == "rightside" {
@@ -927,8 +794,8 @@ pub fn collision_handle(
 						700.,
 						0.,
 					);
-					if player_stats.health-PUNCHATTACK > 0.{ 
-						player_stats.health = player_stats.health-PUNCHATTACK;
+					if player_stats.health-punch_damage > 0.{ 
+						player_stats.health = player_stats.health-punch_damage;
 					} else {
 						player_stats.health = 0.;
 					}
@@ -937,8 +804,8 @@ pub fn collision_handle(
 						350.,
 						0.,
 					);
-					if player_stats.health-(PUNCHATTACK/4.) > 0.{ 
-						player_stats.health = player_stats.health-(PUNCHATTACK/4.);
+					if player_stats.health-(punch_damage/4.) > 0.{ 
+						player_stats.health = player_stats.health-(punch_damage/4.);
 					} else {
 						player_stats.health = 0.;
 					}
@@ -972,8 +839,8 @@ pub fn collision_handle(
 						-700.,
 						0.,
 					);
-					if player_stats.health-PUNCHATTACK > 0.{ 
-						player_stats.health = player_stats.health-PUNCHATTACK;
+					if player_stats.health-punch_damage > 0.{ 
+						player_stats.health = player_stats.health-punch_damage;
 					} else {
 						player_stats.health = 0.;
 					}
@@ -982,8 +849,8 @@ pub fn collision_handle(
 						-350.,
 						0.,
 					);
-					if player_stats.health-(PUNCHATTACK/4.) > 0.{ 
-						player_stats.health = player_stats.health-(PUNCHATTACK/4.);
+					if player_stats.health-(punch_damage/4.) > 0.{ 
+						player_stats.health = player_stats.health-(punch_damage/4.);
 					} else {
 						player_stats.health = 0.;
 					}
@@ -1015,8 +882,8 @@ pub fn collision_handle(
 						1000.,
 						0.,
 					);
-					if player_stats.health - KICKATTACK > 0. {
-						player_stats.health = player_stats.health-20.;
+					if player_stats.health - kick_damage > 0. {
+						player_stats.health = player_stats.health-kick_damage;
 					} else {
 						player_stats.health = 0.;
 					}
@@ -1025,8 +892,8 @@ pub fn collision_handle(
 						500.,
 						0.,
 					);
-					if player_stats.health - (KICKATTACK/4.) > 0. {
-						player_stats.health = player_stats.health-(KICKATTACK/4.);
+					if player_stats.health - (kick_damage/4.) > 0. {
+						player_stats.health = player_stats.health-(kick_damage/4.);
 					} else {
 						player_stats.health = 0.;
 					}
@@ -1059,8 +926,8 @@ pub fn collision_handle(
 						-1000.,
 						0.,
 					);
-					if player_stats.health - KICKATTACK > 0. {
-						player_stats.health = player_stats.health-20.;
+					if player_stats.health - kick_damage > 0. {
+						player_stats.health = player_stats.health-kick_damage;
 					} else {
 						player_stats.health = 0.;
 					}
@@ -1069,8 +936,8 @@ pub fn collision_handle(
 						-500.,
 						0.,
 					);
-					if player_stats.health - (KICKATTACK/4.) > 0. {
-						player_stats.health = player_stats.health-(KICKATTACK/4.);
+					if player_stats.health - (kick_damage/4.) > 0. {
+						player_stats.health = player_stats.health-(kick_damage/4.);
 					} else {
 						player_stats.health = 0.;
 					}
@@ -1566,7 +1433,7 @@ pub fn enemy_take_action(
 	// these last three are to pass to the punch, kick, and block functions
 	enemy_send: EventWriter<CollideEvent>,
 	commands: Commands,
-	level: ResMut<State<Level>>,
+	enemy_profile: Query<&EnemyProfile, With<Enemy>>,
 ) {
 	let (enemy_transform, mut enemy_timer, mut enemy_actions, mut enemy_sprite,mut enemy_stats) = enemy.single_mut();
 	let player_transform = player.single_mut();
@@ -1592,85 +1459,15 @@ pub fn enemy_take_action(
 	if enemy_timer.0.finished() && (!enemy_actions.attacking && !enemy_actions.blocking) && enemy_within_range {
 		enemy_timer.0.reset();
 
-		// choose an action for the enemy to take (punch, kick, or block)
-		let mut next_choice = rng.gen_range(0..3);	// generate 0, 1, or 2 since we have 3 options
-		
-
-
-		match level.current(){
-			Level::Level1 =>{
-				if enemy_stats.health < 20.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level2 =>{
-				if enemy_stats.health < 30.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level3 =>{
-				if enemy_stats.health < 50.0 {
-					enemy_timer.0=Timer::from_seconds(0.5, false);
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level4 =>{
-				enemy_timer.0=Timer::from_seconds(0.5, false);
-				if enemy_stats.health < 60.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-
-			}
-			Level::Level5 => {
-				enemy_timer.0=Timer::from_seconds(0.5, false);
-				if enemy_stats.health < 70.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level6 => {
-				enemy_timer.0=Timer::from_seconds(0.4, false);
-				if enemy_stats.health < 25.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level7 => {
-				enemy_timer.0=Timer::from_seconds(0.4, false);
-				if enemy_stats.health < 40.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level8 => {
-				enemy_timer.0=Timer::from_seconds(0.4, false);
-				if enemy_stats.health < 50.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level9 => {
-				enemy_timer.0=Timer::from_seconds(0.3, false);
-				if enemy_stats.health < 60.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
-			Level::Level10 => {
-				enemy_timer.0=Timer::from_seconds(0.25, false);
-				if enemy_stats.health < 80.0 {
-					let choices=[0,0,0,1,1,1,2];
-					next_choice = choices.into_iter().choose(&mut rng).unwrap();	// generate 0, 1, or 2 since we have 3 options
-				}
-			}
+		// the profile decides how fast the enemy acts and what it likes to do at this health
+		let profile = &enemy_profile.single().0;
+		if let Some(secs) = profile.next_cadence(enemy_stats.health) {
+			enemy_timer.0 = Timer::from_seconds(secs, false);
 		}
 
-		match next_choice {
-			0 => {
+		// choose an action for the enemy to take (punch, kick, or block)
+		match profile.weights(enemy_stats.health).pick(&mut rng) {
+			EnemyAction::Punch => {
 				enemy_punch(
 					enemy_send,
 					player,
@@ -1678,7 +1475,7 @@ pub fn enemy_take_action(
 					enemy
 				);
 			},
-			1 => {
+			EnemyAction::Kick => {
 				enemy_kick(
 					enemy_send,
 					player,
@@ -1686,10 +1483,9 @@ pub fn enemy_take_action(
 					enemy
 				);
 			},
-			2 => {
+			EnemyAction::Block => {
 				enemy_block(&mut enemy_sprite, &mut enemy_actions);
 			},
-			_ => info!("We should never get here..."),
 		}
 	}
 }
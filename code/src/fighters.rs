@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::Deserialize;
+
+// the three things an enemy can decide to do when its action timer runs out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyAction {
+    Punch,
+    Kick,
+    Block,
+}
+
+// relative odds of each action, 1/1/1 is a fair three way pick
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ActionWeights {
+    pub punch: u32,
+    pub kick: u32,
+    pub block: u32,
+}
+
+impl Default for ActionWeights {
+    fn default() -> Self {
+        ActionWeights { punch: 1, kick: 1, block: 1 }
+    }
+}
+
+impl ActionWeights {
+    pub fn pick<R: Rng>(&self, rng: &mut R) -> EnemyAction {
+        let choices = [EnemyAction::Punch, EnemyAction::Kick, EnemyAction::Block];
+        let index = WeightedIndex::new([self.punch, self.kick, self.block])
+            .expect("fighter profiles are validated to have a non-zero weight");
+        choices[index.sample(rng)]
+    }
+
+    // a hand written file could have weights too big to add up, which the pick can't use either
+    fn is_valid(&self) -> bool {
+        self.punch.checked_add(self.kick)
+            .and_then(|sum| sum.checked_add(self.block))
+            .is_some_and(|total| total > 0)
+    }
+}
+
+// how often (in seconds) the enemy gets to act
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Cadence {
+    pub opening: f32,                   // wait before the first action
+    #[serde(default)]
+    pub after_action: Option<f32>,      // wait between later actions, defaults to `opening`
+}
+
+// behavior once the enemy's health drops below `threshold`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LowHealth {
+    pub threshold: f32,
+    #[serde(default)]
+    pub cadence: Option<f32>,
+    pub action_weights: ActionWeights,
+}
+
+// scales the damage of the enemy's attacks on the player
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DamageMultipliers {
+    pub punch: f32,
+    pub kick: f32,
+}
+
+impl Default for DamageMultipliers {
+    fn default() -> Self {
+        DamageMultipliers { punch: 1., kick: 1. }
+    }
+}
+
+// one opponent as written in assets/fighters/<id>.json
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FighterProfile {
+    pub color: [f32; 3],
+    #[serde(default)]
+    pub sprite: Option<String>,
+    pub cadence: Cadence,
+    #[serde(default)]
+    pub action_weights: ActionWeights,
+    pub low_health: LowHealth,
+    #[serde(default)]
+    pub damage_multipliers: DamageMultipliers,
+}
+
+impl FighterProfile {
+    pub fn is_low_health(&self, health: f32) -> bool {
+        health < self.low_health.threshold
+    }
+
+    // how long to wait after an action, None keeps the current timer length
+    pub fn next_cadence(&self, health: f32) -> Option<f32> {
+        if self.is_low_health(health) {
+            self.low_health.cadence.or(self.cadence.after_action)
+        } else {
+            self.cadence.after_action
+        }
+    }
+
+    pub fn weights(&self, health: f32) -> &ActionWeights {
+        if self.is_low_health(health) {
+            &self.low_health.action_weights
+        } else {
+            &self.action_weights
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FighterError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, source: serde_json::Error },
+    MissingSprite { id: String, path: PathBuf },
+    BadCadence(String),
+    NoActions(String),
+    BadDamage(String),
+}
+
+impl fmt::Display for FighterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FighterError::Io { path, source } =>
+                write!(f, "could not read {}: {}", path.display(), source),
+            FighterError::Parse { path, source } =>
+                write!(f, "{} is not a valid fighter profile: {}", path.display(), source),
+            FighterError::MissingSprite { id, path } =>
+                write!(f, "fighter \"{}\" uses sprite {} which does not exist", id, path.display()),
+            FighterError::BadCadence(id) =>
+                write!(f, "fighter \"{}\" has a cadence that is not a positive number of seconds", id),
+            FighterError::NoActions(id) =>
+                write!(f, "fighter \"{}\" has action weights that add up to zero or are too big to add up", id),
+            FighterError::BadDamage(id) =>
+                write!(f, "fighter \"{}\" has a damage multiplier that is negative or not a number", id),
+        }
+    }
+}
+
+impl std::error::Error for FighterError {}
+
+// every fighter profile, keyed by the file name it was loaded from
+pub struct FighterRoster {
+    profiles: HashMap<String, FighterProfile>,
+}
+
+impl FighterRoster {
+    // loads every .json file in `dir`, sprites are checked against `asset_dir`
+    pub fn load_dir(dir: &Path, asset_dir: &Path) -> Result<Self, FighterError> {
+        let io_err = |source| FighterError::Io { path: dir.to_path_buf(), source };
+        let mut profiles = HashMap::new();
+        for entry in fs::read_dir(dir).map_err(io_err)? {
+            let path = entry.map_err(io_err)?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let id = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let json = fs::read_to_string(&path).map_err(|source| FighterError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let profile: FighterProfile = serde_json::from_str(&json).map_err(|source| FighterError::Parse {
+                path: path.clone(),
+                source,
+            })?;
+            validate(&id, &profile, asset_dir)?;
+            profiles.insert(id, profile);
+        }
+        Ok(FighterRoster { profiles })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&FighterProfile> {
+        self.profiles.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+}
+
+fn validate(id: &str, profile: &FighterProfile, asset_dir: &Path) -> Result<(), FighterError> {
+    if let Some(sprite) = &profile.sprite {
+        let path = asset_dir.join(sprite);
+        if !path.is_file() {
+            return Err(FighterError::MissingSprite { id: id.to_string(), path });
+        }
+    }
+    let cadences = [Some(profile.cadence.opening), profile.cadence.after_action, profile.low_health.cadence];
+    // NaN and infinite cadences would never fire
+    if cadences.iter().flatten().any(|secs| !secs.is_finite() || *secs <= 0.) {
+        return Err(FighterError::BadCadence(id.to_string()));
+    }
+    if !profile.action_weights.is_valid() || !profile.low_health.action_weights.is_valid() {
+        return Err(FighterError::NoActions(id.to_string()));
+    }
+    // a negative multiplier would heal the player, NaN or infinite ones break their health bar
+    let damage = &profile.damage_multipliers;
+    if [damage.punch, damage.kick].iter().any(|multiplier| !multiplier.is_finite() || *multiplier < 0.) {
+        return Err(FighterError::BadDamage(id.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_profiles_are_valid() {
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let fighters = FighterRoster::load_dir(&asset_dir.join("fighters"), &asset_dir).unwrap();
+        let gloria = fighters.get("gloria").unwrap();
+        assert_eq!(gloria.next_cadence(80.), None);
+        assert_eq!(gloria.next_cadence(40.), Some(0.5));
+        assert_eq!(gloria.weights(40.).punch, 3);
+
+        let mut broken = gloria.clone();
+        broken.damage_multipliers.kick = -1.;
+        assert!(matches!(validate("gloria", &broken, &asset_dir), Err(FighterError::BadDamage(_))));
+        broken.damage_multipliers.kick = f32::NAN;
+        assert!(matches!(validate("gloria", &broken, &asset_dir), Err(FighterError::BadDamage(_))));
+        broken = gloria.clone();
+        broken.action_weights = ActionWeights { punch: u32::MAX, kick: 1, block: 0 };
+        assert!(matches!(validate("gloria", &broken, &asset_dir), Err(FighterError::NoActions(_))));
+    }
+}
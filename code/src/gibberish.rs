@@ -0,0 +1,121 @@
+use serde::Deserialize;
+
+use crate::emotion;
+use crate::sentiment::{simplify, SentimentBreakdown};
+
+// why a neighbor couldn't make sense of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nonsense {
+    Empty,      // just pressed enter
+    TooShort,   // a letter or two
+    NotWords,   // keyboard mashing and the like
+}
+
+// how a neighbor reacts to lines they can't make out, the "confusion" of a neighbor in assets/neighbors.json
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Confusion {
+    pub enabled: bool,          // false scores every line like before, however strange
+    pub min_letters: usize,     // lines with fewer letters and digits than this are too short to mean anything
+    pub min_words: f64,         // how much of a line has to look like real words, 0 to 1
+    pub costs_turn: bool,       // an impatient neighbor counts the line as a turn anyway
+}
+
+impl Default for Confusion {
+    fn default() -> Self {
+        Confusion {
+            enabled: true,
+            min_letters: 2,
+            min_words: 0.5,
+            costs_turn: false,
+        }
+    }
+}
+
+const VOWELS: [char; 6] = ['a', 'e', 'i', 'o', 'u', 'y'];
+// no english word has more consonants than this in a row ("strengths" has four)
+const MAX_CONSONANTS: usize = 4;
+// sounds people type that have no vowel in them but still mean something
+const INTERJECTIONS: [&str; 12] = ["hm", "hmm", "mhm", "mm", "mmm", "sh", "shh", "psst", "pst", "tsk", "pfft", "brr"];
+const KEYBOARD_ROWS: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+// this many keys in a row along the keyboard is mashing, not a word
+const MASHED_KEYS: usize = 4;
+
+impl Confusion {
+    // what's wrong with the line, or none if the neighbor should score it
+    pub fn check(&self, text: &str, breakdown: &SentimentBreakdown) -> Option<Nonsense> {
+        if !self.enabled {
+            return None;
+        }
+        if text.trim().is_empty() {
+            return Some(Nonsense::Empty);
+        }
+        // a lone smiley is short but the lexicon still knows what it means
+        if breakdown.matched.is_empty() && text.chars().filter(|c| c.is_alphanumeric()).count() < self.min_letters {
+            return Some(Nonsense::TooShort);
+        }
+        let words = breakdown.words.len();
+        // "grr" doesn't look like much but it's as real as any word the lexicons or the mood tracker know
+        let known: Vec<String> = breakdown.matched.iter().flat_map(|(word, _)| simplify(word)).collect();
+        let real = breakdown.words.iter()
+            .filter(|word| looks_like_word(word) || known.contains(word) || emotion::is_emotion_word(word))
+            .count();
+        if words == 0 || (real as f64) < self.min_words * words as f64 {
+            return Some(Nonsense::NotWords);
+        }
+        None
+    }
+}
+
+// a rough guess at whether a simplified word could be english, emoji, numbers and other scripts get the benefit of the doubt
+pub fn looks_like_word(word: &str) -> bool {
+    if !word.chars().any(|c| c.is_ascii_alphabetic()) || word.chars().any(|c| c.is_alphabetic() && !c.is_ascii()) {
+        return true;
+    }
+    let letters: String = word.chars().filter(char::is_ascii_alphabetic).collect();
+    if INTERJECTIONS.contains(&letters.as_str()) {
+        return true;
+    }
+    if !letters.contains(VOWELS) {
+        return false;
+    }
+    let mut consonants = 0;
+    for c in letters.chars() {
+        consonants = if VOWELS.contains(&c) { 0 } else { consonants + 1 };
+        if consonants > MAX_CONSONANTS {
+            return false;
+        }
+    }
+    let mashed = letters.len() >= MASHED_KEYS && KEYBOARD_ROWS.iter().any(|row| {
+        row.contains(letters.as_str()) || row.chars().rev().collect::<String>().contains(letters.as_str())
+    });
+    !mashed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::score_utterance;
+
+    #[test]
+    fn nonsense_is_caught_and_real_lines_are_not() {
+        let confusion = Confusion::default();
+        let check = |text: &str| confusion.check(text, &score_utterance(text));
+        assert_eq!(check(""), Some(Nonsense::Empty));
+        assert_eq!(check("   "), Some(Nonsense::Empty));
+        assert_eq!(check("h"), Some(Nonsense::TooShort));
+        assert_eq!(check("asdfgh"), Some(Nonsense::NotWords));
+        assert_eq!(check("jkjkjk xzcvb"), Some(Nonsense::NotWords));
+        assert_eq!(check("strengths"), None);
+        assert_eq!(check(":)"), None);
+        assert_eq!(check("ok"), None);
+        for interjection in ["hmm", "hmmmm", "shh", "psst", "Shhh!", "mhm, sure"] {
+            assert_eq!(check(interjection), None, "{}", interjection);
+        }
+        assert_eq!(check("sorry lkjhg, typo"), None);
+        // an emotion word has to get through to the mood tracker even if it isn't spelled like a word
+        assert_eq!(check("grr"), None);
+        assert_eq!(crate::emotion::feel(&score_utterance("grr")).anger, 2.);
+        assert_eq!(Confusion { enabled: false, ..Confusion::default() }.check("", &score_utterance("")), None);
+    }
+}
@@ -0,0 +1,133 @@
+use serde::Deserialize;
+
+use crate::sentiment::{mentions, simplify, SentimentBreakdown};
+
+// what the player is trying to do with a line, on top of how nice it sounds
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Intent {
+    Apology,
+    Offer,
+    Question,
+    Threat,
+    Insult,
+}
+
+const APOLOGIES: [&str; 9] = ["sorry", "apologize", "apologise", "apologies", "my bad", "my fault", "forgive me",
+    "pardon me", "i regret"];
+const OFFERS: [&str; 13] = ["i'll", "i will", "let me", "i can", "i could", "happy to", "here you go", "here you are",
+    "i'm going to", "i am going to", "want me to", "would you like", "allow me"];
+const QUESTION_STARTS: [&str; 17] = ["what", "why", "how", "when", "where", "who", "which", "can you", "could you",
+    "would you", "will you", "do you", "did you", "are you", "is it", "is there", "should i"];
+const THREATS: [&str; 17] = ["call the police", "call the cops", "call my lawyer", "sue you", "report you", "kill you",
+    "hurt you", "fight you", "fight me", "beat you", "punch you", "kick your", "you'll regret", "you will regret",
+    "watch your back", "or else", "see you in court"];
+// only insults when they're about the listener, "you clown" but not "the clown at the party"
+const INSULTS: [&str; 18] = ["idiot", "moron", "jerk", "loser", "stupid", "dumb", "ugly", "creep", "weirdo", "freak",
+    "clown", "fool", "pathetic", "useless", "psycho", "trash", "garbage", "pig"];
+// only insults when they're said as an order, "go away!" but not "did your dog get lost?"
+const DISMISSALS: [&str; 4] = ["shut up", "get lost", "go away", "screw you"];
+// what can come before an order and still leave it an order, "oh just go away"
+const SOFTENERS: [&str; 4] = ["oh", "just", "now", "please"];
+const SECOND_PERSON: [&str; 4] = ["you", "you're", "your", "yourself"];
+// how far after "you" a mean word still counts as being about them
+const INSULT_WINDOW: usize = 3;
+
+// what a neighbor thinks of each intent when it isn't in their "intents" in assets/neighbors.json
+pub fn default_weight(intent: Intent) -> f64 {
+    match intent {
+        Intent::Apology => 3.,
+        Intent::Offer => 3.,
+        Intent::Question => 0.5,
+        Intent::Threat => -8.,
+        Intent::Insult => -4.,
+    }
+}
+
+// the intents in one line, rule based so it can be tested and works without a network,
+// a line can have several ("sorry, can I help?") and a threat is never also an offer ("I'll call the police")
+pub fn detect(text: &str, breakdown: &SentimentBreakdown) -> Vec<Intent> {
+    let words = &breakdown.words;
+    let mut intents = Vec::new();
+
+    let negators = simplify("not don't never");
+    let apologized = APOLOGIES.iter().any(|apology| {
+        let apology = simplify(apology);
+        (0..words.len()).any(|start| {
+            words[start..].starts_with(&apology)
+                // "I'm not sorry" is not an apology
+                && !words[start.saturating_sub(2)..start].iter().any(|word| negators.contains(word))
+        })
+    });
+    if apologized {
+        intents.push(Intent::Apology);
+    }
+
+    let threatened = THREATS.iter().any(|threat| mentions(words, threat));
+    if !threatened && OFFERS.iter().any(|offer| mentions(words, offer)) {
+        intents.push(Intent::Offer);
+    }
+
+    let asked = text.contains('?') || QUESTION_STARTS.iter().any(|start| words.starts_with(&simplify(start)));
+    if asked {
+        intents.push(Intent::Question);
+    }
+
+    if threatened {
+        intents.push(Intent::Threat);
+    }
+
+    // "you" followed closely by an insult or something the lexicon thinks is mean, or an order to go away,
+    // after "your" only a real insult counts since the mean word is usually about their things ("your dog got lost")
+    let second_person: Vec<String> = SECOND_PERSON.iter().flat_map(|word| simplify(word)).collect();
+    let possessive = simplify("your");
+    let insults: Vec<String> = INSULTS.iter().flat_map(|insult| simplify(insult)).collect();
+    // matches are kept as typed, the line's words are simplified
+    let mean_words: Vec<String> = breakdown.matched.iter()
+        .filter(|(_, value)| *value <= -2.)
+        .flat_map(|(word, _)| simplify(word))
+        .collect();
+    let softeners: Vec<String> = SOFTENERS.iter().flat_map(|word| simplify(word)).collect();
+    let dismissed = text.split([',', '.', '!', '?', ';'])
+        .map(|clause| simplify(clause).into_iter().skip_while(|word| softeners.contains(word)).collect::<Vec<String>>())
+        .any(|clause| DISMISSALS.iter().any(|dismissal| clause.starts_with(&simplify(dismissal))));
+    let insulted = dismissed
+        || words.iter().enumerate().any(|(index, word)| {
+            second_person.contains(word)
+                && words[index + 1..words.len().min(index + 1 + INSULT_WINDOW)].iter()
+                    .any(|next| insults.contains(next) || (!possessive.contains(word) && mean_words.contains(next)))
+        });
+    if insulted {
+        intents.push(Intent::Insult);
+    }
+    intents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::score_utterance;
+
+    fn intents(text: &str) -> Vec<Intent> {
+        detect(text, &score_utterance(text))
+    }
+
+    #[test]
+    fn lines_are_sorted_into_intents() {
+        assert_eq!(intents("I'm so sorry about that"), vec![Intent::Apology]);
+        assert_eq!(intents("I'm not sorry"), Vec::<Intent>::new());
+        assert_eq!(intents("I'll move my car right now"), vec![Intent::Offer]);
+        assert_eq!(intents("my bad, want me to help?"), vec![Intent::Apology, Intent::Offer, Intent::Question]);
+        assert_eq!(intents("what kind of dog is it"), vec![Intent::Question]);
+        assert_eq!(intents("I'll call the police"), vec![Intent::Threat]);
+        assert_eq!(intents("you're a moron"), vec![Intent::Insult]);
+        assert_eq!(intents("you are so annoying"), vec![Intent::Insult]);
+        assert_eq!(intents("that movie was annoying"), Vec::<Intent>::new());
+        assert_eq!(intents("oh just go away!"), vec![Intent::Insult]);
+        assert_eq!(intents("you lazy pig"), vec![Intent::Insult]);
+        assert_eq!(intents("your stupid dog"), vec![Intent::Insult]);
+        // the same words about something else aren't aimed at anyone
+        assert_eq!(intents("did your dog get lost?"), vec![Intent::Question]);
+        assert_eq!(intents("will you help me take out the garbage?"), vec![Intent::Question]);
+    }
+}
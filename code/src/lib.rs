@@ -1,3 +1,16 @@
+pub mod campaign;
+pub mod emotion;
+pub mod fighters;
+pub mod gibberish;
+pub mod intent;
+pub mod politeness;
+pub mod roster;
+pub mod save;
+pub mod scoring;
+pub mod sentiment;
+pub mod session;
+pub mod topic;
+
 #[cfg(test)]
 mod tests {
     #[test]
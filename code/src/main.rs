@@ -1,7 +1,12 @@
 use bevy::{
 	prelude::*,
 	window::PresentMode,
+	asset::FileAssetIo,
+	ui::FocusPolicy,
 };
+use std::path::PathBuf;
+use code::campaign::Campaign;
+use code::save::{Outcome, SaveGame};
 
 mod fight;
 mod conversation;
@@ -18,19 +23,6 @@ enum GameState {
 	LevelChange,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub enum Level {
-	Level1,
-    Level2,
-    Level3,
-    Level4,
-	Level5,
-	Level6,
-	Level7,
-	Level8,
-	Level9,
-	Level10,
-}
 #[derive(Component, Deref, DerefMut)]
 struct PopupTimer(Timer);
 #[derive(Component, Deref, DerefMut)]
@@ -40,9 +32,17 @@ pub struct IsStart();
 #[derive(Component)]
 pub struct IsLevel();
 #[derive(Component)]
-pub struct CreditsButton();
+pub struct MenuButton {
+	action: MenuAction,
+	order: usize,	// position in the keyboard/gamepad focus order
+}
 #[derive(Component)]
-pub struct StartButton();
+pub struct ButtonArt {
+	normal: Handle<Image>,
+	pressed: Handle<Image>,
+}
+#[derive(Default)]
+pub struct MenuFocus(usize);
 #[derive(Component, Deref, DerefMut)]
 struct DespawnTimer(Timer);
 pub struct ConvInputEvent(String);
@@ -54,6 +54,13 @@ pub struct FightLossEvent();
 
 pub struct CollideEvent(bool,String);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuAction {
+	Start,
+	Credits,
+	Continue,
+}
+
 
 fn main() {
 	App::new()
@@ -66,7 +73,6 @@ fn main() {
 		})
 		.insert_resource(ClearColor(Color::BLACK))
 		.add_state(GameState::Start)	//start the game in the fight state
-		.add_state(Level::Level1)	//start the game on level 1
 		.add_event::<ConvInputEvent>()
 		.add_event::<ConvLossEvent>()
 		.add_event::<ConvWinEvent>()
@@ -75,6 +81,11 @@ fn main() {
 		.add_event::<FightLossEvent>()
 		.add_plugins(DefaultPlugins)
 		.add_startup_system(setup)
+		.add_startup_system(load_save)
+		.add_startup_system(conversation::load_roster)
+		.add_startup_system(fight::load_fighters)
+		.add_startup_system_to_stage(StartupStage::PostStartup, load_campaign)
+		.add_startup_system_to_stage(StartupStage::PostStartup, conversation::load_lexicons)
 		.add_system_set(
 			SystemSet::on_update(GameState::Credits)
 				.label("credits")
@@ -107,11 +118,17 @@ fn main() {
 				.with_system(setup_start)
 		)
 		.add_system(animate_start)
+		.init_resource::<MenuFocus>()
 		.add_system_set(
 			SystemSet::on_enter(GameState::Start)
-				.with_system(start_button)
+				.with_system(setup_menu)
+		)
+		.add_system_set(
+			SystemSet::on_update(GameState::Start)
+				.label("menu")
+				.with_system(button_system)
+				.with_system(button_visuals)
 		)
-		.add_system(button_system)
 		.add_system_set(
 			SystemSet::on_exit(GameState::Start)
 				.with_system(clear_start)
@@ -158,6 +175,53 @@ fn main() {
 		.run();
 }
 
+// the same folder the AssetServer loads from, for data files we read ourselves at startup
+pub fn asset_dir() -> PathBuf {
+	FileAssetIo::get_root_path().join("assets")
+}
+
+const CAMPAIGN_PATH: &str = "campaign.json";
+
+// Reads the ordered level list once the roster and fighter profiles are loaded so every level can be checked against them
+fn load_campaign(
+	mut commands: Commands,
+	roster: Res<code::roster::NeighborRoster>,
+	fighters: Res<code::fighters::FighterRoster>,
+) {
+	let campaign = match Campaign::load(&asset_dir().join(CAMPAIGN_PATH)) {
+		Ok(campaign) => campaign,
+		Err(e) => panic!("Could not load the campaign: {}", e),
+	};
+	if let Err(e) = campaign.validate(&roster, &fighters) {
+		panic!("Campaign does not match the game data: {}", e);
+	}
+	info!("Loaded a campaign with {} levels", campaign.len());
+	commands.insert_resource(campaign);	//start the game on the first level
+}
+
+// Reads the save file from the user's data directory, a missing or broken save just starts a fresh game
+fn load_save(mut commands: Commands, mut windows: ResMut<Windows>) {
+	let save = match SaveGame::default_path().and_then(|path| SaveGame::load(&path)) {
+		Ok(save) => save,
+		Err(e) => {
+			warn!("Starting without a save: {}", e);
+			SaveGame::default()
+		}
+	};
+	// the player's settings go on the window that's already open
+	if let Some(window) = windows.get_primary_mut() {
+		window.set_present_mode(if save.settings.vsync { PresentMode::Fifo } else { PresentMode::Immediate });
+	}
+	commands.insert_resource(save);
+}
+
+fn write_save(save: &SaveGame) {
+	match SaveGame::default_path().and_then(|path| save.save(&path)) {
+		Ok(_) => info!("Progress saved"),
+		Err(e) => warn!("Could not save progress: {}", e),
+	}
+}
+
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 	commands.spawn_bundle(Camera2dBundle::default());
 	commands.spawn_bundle(TextBundle::from_section(
@@ -229,88 +293,236 @@ fn clear_start(
         commands.entity(e).despawn();
     }*/
 	for (e, _start) in query.iter_mut(){
-		commands.entity(e).despawn();	
+		commands.entity(e).despawn_recursive();	//the menu buttons have children
 	}
 }
 
-fn start_button(
+// the button art is a 150x150 picture with the actual button drawn near the top,
+// these are the edges of the drawn part (in picture pixels) so only it is clickable
+const ART_SCALE: f32 = 1.5;
+const ART_SIZE: f32 = 150.;
+const ART_LEFT: f32 = 8.;
+const ART_TOP: f32 = 26.;
+const ART_WIDTH: f32 = 134.;
+const ART_HEIGHT: f32 = 50.;
+
+fn setup_menu(
 	mut commands: Commands,
 	asset_server: Res<AssetServer>,
+	save: Res<SaveGame>,
+	mut focus: ResMut<MenuFocus>,
 ){
-	commands.spawn_bundle(SpriteBundle {
-		texture: asset_server.load("buttons/startbutton.png"),
-		transform: Transform::from_xyz(0., 0., 1.1).with_scale(Vec3::splat(1.5)),
-		..default()
-	})
-	.insert(IsStart())
-	.insert(StartButton());
-
-	commands.spawn_bundle(SpriteBundle {
-		texture: asset_server.load("buttons/creditsbutton.png"),
-		transform: Transform::from_xyz(0., -90., 1.1).with_scale(Vec3::splat(1.5)),
+	let buttons = vec![
+		(MenuAction::Start, "buttons/startbutton.png", "buttons/startpress.png"),
+		(MenuAction::Credits, "buttons/creditsbutton.png", "buttons/creditspress.png"),
+	];
+	let continue_order = buttons.len();
+	let button_size = Size::new(Val::Px(ART_WIDTH * ART_SCALE), Val::Px(ART_HEIGHT * ART_SCALE));
+
+	// the buttons hang down from the middle of the screen, under the title
+	commands.spawn_bundle(NodeBundle {
+		style: Style {
+			size: Size::new(Val::Percent(100.), Val::Percent(50.)),
+			flex_direction: FlexDirection::ColumnReverse,	//top to bottom
+			justify_content: JustifyContent::FlexStart,
+			align_items: AlignItems::Center,
+			..default()
+		},
+		color: Color::NONE.into(),
 		..default()
 	})
 	.insert(IsStart())
-	.insert(CreditsButton());
-
-	commands.spawn_bundle(SpriteBundle {
-		texture: asset_server.load("buttons/startpress.png"),
-		transform: Transform::from_xyz(0., 0., 1.).with_scale(Vec3::splat(1.5)),
-		..default()
-	})
-	.insert(IsStart());
+	.with_children(|menu| {
+		for (order, (action, normal, pressed)) in buttons.into_iter().enumerate() {
+			let art = ButtonArt {
+				normal: asset_server.load(normal),
+				pressed: asset_server.load(pressed),
+			};
+			menu.spawn_bundle(ButtonBundle {
+				style: Style {
+					size: button_size,
+					margin: UiRect::all(Val::Px(8.)),
+					overflow: Overflow::Hidden,
+					..default()
+				},
+				color: Color::NONE.into(),
+				..default()
+			})
+			.insert(MenuButton { action, order })
+			.with_children(|button| {
+				// the whole picture, shifted so the drawn button lines up with the clickable node
+				button.spawn_bundle(ImageBundle {
+					style: Style {
+						size: Size::new(Val::Px(ART_SIZE * ART_SCALE), Val::Px(ART_SIZE * ART_SCALE)),
+						position_type: PositionType::Absolute,
+						position: UiRect {
+							left: Val::Px(-ART_LEFT * ART_SCALE),
+							top: Val::Px((ART_TOP + ART_HEIGHT - ART_SIZE) * ART_SCALE),	//ui y goes up, so this is the bottom edge
+							..default()
+						},
+						..default()
+					},
+					image: UiImage(art.normal.clone()),
+					focus_policy: FocusPolicy::Pass,
+					..default()
+				})
+				.insert(art);
+			});
+		}
 
-	commands.spawn_bundle(SpriteBundle {
-		texture: asset_server.load("buttons/creditspress.png"),
-		transform: Transform::from_xyz(0., -90., 1.).with_scale(Vec3::splat(1.5)),
-		..default()
-	})
-	.insert(IsStart());
+		// only offer to continue if there is a campaign in progress
+		if save.has_progress() {
+			menu.spawn_bundle(ButtonBundle {
+				style: Style {
+					size: button_size,
+					margin: UiRect::all(Val::Px(8.)),
+					justify_content: JustifyContent::Center,
+					align_items: AlignItems::Center,
+					..default()
+				},
+				color: CONTINUE_NORMAL.into(),
+				..default()
+			})
+			.insert(MenuButton { action: MenuAction::Continue, order: continue_order })
+			.with_children(|button| {
+				button.spawn_bundle(TextBundle::from_section("Continue", TextStyle {
+					font: asset_server.load("Fonts/Minecraft.ttf"),
+					font_size: 40.0,
+					color: Color::WHITE,
+				}));
+			});
+		}
+	});
 
+	// a returning player most likely wants to pick up where they left off
+	focus.0 = if save.has_progress() { continue_order } else { 0 };
 }
 
-
+// mouse clicks, plus arrow keys/WASD/tab or the d-pad to move between buttons and enter/space/A to press one
 fn button_system(
 	mut game_state: ResMut<State<GameState>>,
-	windows: Res<Windows>,
-	buttons: Res<Input<MouseButton>>,
+	keys: Res<Input<KeyCode>>,
+	gamepad_buttons: Res<Input<GamepadButton>>,
+	mut typed: ResMut<Events<ReceivedCharacter>>,
+	interactions: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+	buttons: Query<&MenuButton>,
+	mut focus: ResMut<MenuFocus>,
+	mut campaign: ResMut<Campaign>,
+	mut save: ResMut<SaveGame>,
 ){
-	let window = windows.get_primary().unwrap();
-	
-	let cursor_position = if let Some(cursor_position) = windows
-        .get_primary()
-        .and_then(|window| window.cursor_position())
-    {
-        cursor_position
-    } else {
-        return;
-    };
-
-	let mouse_clicked = buttons.just_pressed(MouseButton::Left);
-	
-	if mouse_clicked{
-		if (425. > cursor_position.y) & 
-			(375. < cursor_position.y) &
-			(725. > cursor_position.x) &
-			(550. < cursor_position.x)
-		{
+	let count = buttons.iter().count();
+	if count == 0 {
+		return;
+	}
 
+	for (interaction, button) in interactions.iter() {
+		match *interaction {
+			Interaction::Clicked => {
+				focus.0 = button.order;
+				menu_action(button.action, &mut game_state, &mut campaign, &mut save);
+				return;
+			}
+			Interaction::Hovered => focus.0 = button.order,
+			Interaction::None => {}
+		}
+	}
+
+	let mut up = keys.just_pressed(KeyCode::Up) || keys.just_pressed(KeyCode::W);
+	let mut down = keys.just_pressed(KeyCode::Down) || keys.just_pressed(KeyCode::S) || keys.just_pressed(KeyCode::Tab);
+	let mut press = keys.just_pressed(KeyCode::Return) || keys.just_pressed(KeyCode::Space);
+	for gamepad_button in gamepad_buttons.get_just_pressed() {
+		match gamepad_button.button_type {
+			GamepadButtonType::DPadUp => up = true,
+			GamepadButtonType::DPadDown => down = true,
+			GamepadButtonType::South => press = true,
+			_ => {}
+		}
+	}
+
+	if up {
+		focus.0 = (focus.0 + count - 1) % count;
+	}
+	if down {
+		focus.0 = (focus.0 + 1) % count;
+	}
+	if press {
+		// the enter/space that pressed the button shouldn't end up typed into the conversation
+		typed.clear();
+		if let Some(button) = buttons.iter().find(|button| button.order == focus.0) {
+			menu_action(button.action, &mut game_state, &mut campaign, &mut save);
+		}
+	}
+}
+
+fn menu_action(
+	action: MenuAction,
+	game_state: &mut State<GameState>,
+	campaign: &mut Campaign,
+	save: &mut SaveGame,
+) {
+	match action {
+		MenuAction::Start => {
+			// a new game starts the campaign over
+			campaign.restart();
+			save.start_new(campaign.current());
 			match game_state.set(GameState::Conversation) {
-					Ok(_) => info!("GameState: Conversation"),
-					Err(_) => (),
-				}
+				Ok(_) => info!("GameState: Conversation"),
+				Err(_) => (),
+			}
 		}
-		else if (340. > cursor_position.y) & 
-			(280. < cursor_position.y) &
-			(740. > cursor_position.x) &
-			(535. < cursor_position.x)
-		{		match game_state.set(GameState::Credits) {
-					Ok(_) => info!("GameState: Credits"),
-					Err(_) => (),
-				}
+		MenuAction::Credits => {
+			match game_state.set(GameState::Credits) {
+				Ok(_) => info!("GameState: Credits"),
+				Err(_) => (),
+			}
 		}
-		else{
+		MenuAction::Continue => {
+			// pick up from the saved level, if it was taken out of the campaign start over instead
+			let level = match save.level.clone() {
+				Some(level) => level,
+				None => return,
+			};
+			if !campaign.jump_to(&level) {
+				campaign.restart();
+				save.start_new(campaign.current());
+			}
+			match game_state.set(GameState::Conversation) {
+				Ok(_) => info!("GameState: Conversation (continued at {})", level),
+				Err(_) => (),
+			}
+		}
+	}
+}
+
+const FOCUS_TINT: Color = Color::rgb(1., 0.85, 0.4);
+const CONTINUE_NORMAL: Color = Color::rgba(0., 0., 0., 0.6);
+const CONTINUE_FOCUSED: Color = Color::rgba(0.35, 0.3, 0.1, 0.8);
+const CONTINUE_PRESSED: Color = Color::rgba(0.6, 0.5, 0.15, 0.9);
 
+// pressed buttons swap to their "press" art, hovered or focused buttons get tinted
+fn button_visuals(
+	focus: Res<MenuFocus>,
+	mut buttons: Query<(&Interaction, &MenuButton, &Children, &mut UiColor)>,
+	mut art: Query<(&ButtonArt, &mut UiImage, &mut UiColor), Without<MenuButton>>,
+){
+	for (interaction, button, children, mut color) in buttons.iter_mut() {
+		let pressed = *interaction == Interaction::Clicked;
+		let focused = pressed || *interaction == Interaction::Hovered || button.order == focus.0;
+
+		if button.action == MenuAction::Continue {
+			*color = UiColor(match (pressed, focused) {
+				(true, _) => CONTINUE_PRESSED,
+				(false, true) => CONTINUE_FOCUSED,
+				(false, false) => CONTINUE_NORMAL,
+			});
+			continue;
+		}
+
+		for child in children.iter() {
+			if let Ok((art, mut image, mut tint)) = art.get_mut(*child) {
+				image.0 = if pressed { art.pressed.clone() } else { art.normal.clone() };
+				*tint = UiColor(if focused { FOCUS_TINT } else { Color::WHITE });
+			}
 		}
 	}
 }
@@ -431,7 +643,8 @@ fn conv_over(
 	mut game_state: ResMut<State<GameState>>,
 	mut loss_reader: EventReader<ConvLossEvent>,
 	mut win_reader: EventReader<ConvWinEvent>,
-	mut level: ResMut<State<Level>>,
+	mut campaign: ResMut<Campaign>,
+	mut save: ResMut<SaveGame>,
 ) {
 	for _ev in loss_reader.iter() {
 		match game_state.set(GameState::Fight){
@@ -441,56 +654,44 @@ fn conv_over(
 	}
 	for _ev in win_reader.iter() {
 		println!("we are in conversation!");
-		//Check which level to see what next level should be
-		match level.current(){
-			Level::Level1 =>{
-				level.set(Level::Level2);
-			}
-			Level::Level2 =>{
-				level.set(Level::Level3);
-			}
-			Level::Level3 =>{
-				level.set(Level::Level4);
-			}Level::Level4 =>{
-				level.set(Level::Level5);
-			}
-			Level::Level5 =>{ //if this is the last level, then we won the game
-				level.set(Level::Level6);
-			}
-			Level::Level6 =>{
-				level.set(Level::Level7);
-			}
-			Level::Level7 =>{
-				level.set(Level::Level8);
-			}
-			Level::Level8 =>{
-				level.set(Level::Level9);
-			}Level::Level9 =>{
-				level.set(Level::Level10);
-			}
-			Level::Level10 =>{ //if this is the last level, then we won the game
-				match game_state.set(GameState::Credits){
-					Ok(_) => info!("GameState: Credits"),
-					Err(_) => (),
-				}
-			}
-		}
+		next_level(&mut campaign, &mut game_state, &mut save, Outcome::Talked);
+	}
+}
+
+// Moves the campaign on to its next level, if that was the last level then we won the game
+// Every level beaten is a checkpoint, so the save file is written here
+fn next_level(
+	campaign: &mut Campaign,
+	game_state: &mut State<GameState>,
+	save: &mut SaveGame,
+	outcome: Outcome,
+) {
+	save.record(campaign.current(), outcome);
+	if campaign.advance() {
+		save.level = Some(campaign.current().clone());
 		match game_state.set(GameState::LevelChange){
 			Ok(_) => info!("GameState: LevelChange"),
 			Err(_) => (),
 		}
+	} else {
+		save.level = None;
+		match game_state.set(GameState::Credits){
+			Ok(_) => info!("GameState: Credits"),
+			Err(_) => (),
+		}
 	}
+	write_save(save);
 }
 
 // changes the current gamestate on keypress{}
 fn change_gamestate(
 	keys: Res<Input<KeyCode>>,
 	mut game_state: ResMut<State<GameState>>,
-	mut level: ResMut<State<Level>>
+	mut campaign: ResMut<Campaign>,
 
 ) {
 	if keys.pressed(KeyCode::Key1) {	// change GameState to Start
-		level.set(Level::Level1); //set level back to level 1
+		campaign.restart(); //set level back to the first level
 		match game_state.set(GameState::Start) {
 			Ok(_) => info!("GameState: Start"),
 			Err(_) => (),
@@ -578,7 +779,8 @@ fn fight_over(
 	mut game_state: ResMut<State<GameState>>,
 	mut loss_reader: EventReader<FightLossEvent>,
 	mut win_reader: EventReader<FightWinEvent>,
-	mut level: ResMut<State<Level>>,
+	mut campaign: ResMut<Campaign>,
+	mut save: ResMut<SaveGame>,
 ) {
 	for _ev in loss_reader.iter() {
 		match game_state.set(GameState::Credits){
@@ -588,42 +790,6 @@ fn fight_over(
 	}
 	for _ev in win_reader.iter() {
 		println!("we are in fight!");
-		match level.current(){
-			Level::Level1 =>{
-				level.set(Level::Level2);
-			}
-			Level::Level2 =>{
-				level.set(Level::Level3);
-			}
-			Level::Level3 =>{
-				level.set(Level::Level4);
-			}Level::Level4 =>{
-				level.set(Level::Level5);
-			}
-			Level::Level5 =>{ //if this is the last level, then we won the game
-				level.set(Level::Level6);
-			}
-			Level::Level6 =>{
-				level.set(Level::Level7);
-			}
-			Level::Level7 =>{
-				level.set(Level::Level8);
-			}
-			Level::Level8 =>{
-				level.set(Level::Level9);
-			}Level::Level9 =>{
-				level.set(Level::Level10);
-			}
-			Level::Level10 =>{ //if this is the last level, then we won the game
-				match game_state.set(GameState::Credits){
-					Ok(_) => info!("GameState: Credits"),
-					Err(_) => (),
-				}
-			}
-		}
-		match game_state.set(GameState::LevelChange){
-			Ok(_) => info!("GameState: LevelChange"),
-			Err(_) => (),
-		}
+		next_level(&mut campaign, &mut game_state, &mut save, Outcome::Fought);
 	}
-}
\ No newline at end of file
+}
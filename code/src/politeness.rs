@@ -0,0 +1,112 @@
+use serde::Deserialize;
+
+use crate::sentiment::{mentions, segment, simplify, SentimentBreakdown};
+
+// the kinds of good manners a neighbor can notice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    Please,
+    Thanks,
+    Formal,     // sir, ma'am, excuse me and the like
+    Name,       // talking to the neighbor by name
+}
+
+const PLEASE: [&str; 5] = ["please", "kindly", "would you mind", "if you don't mind", "if it's okay"];
+const THANKS: [&str; 5] = ["thank", "thanks", "appreciate", "grateful", "much obliged"];
+const FORMAL: [&str; 11] = ["sir", "ma'am", "madam", "mister", "mr", "mrs", "ms", "miss", "excuse me", "pardon me",
+    "good morning"];
+// what can go in front of a name that's being used to talk to someone
+const TITLES: [&str; 8] = ["sir", "ma'am", "madam", "mister", "mr", "mrs", "ms", "miss"];
+
+// what each marker is worth to a neighbor, the "politeness" of a neighbor in assets/neighbors.json
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolitenessBonuses {
+    pub please: f64,
+    pub thanks: f64,
+    pub formal: f64,
+    pub name: f64,
+}
+
+impl Default for PolitenessBonuses {
+    fn default() -> Self {
+        PolitenessBonuses {
+            please: 1.,
+            thanks: 1.,
+            formal: 0.5,
+            name: 1.5,
+        }
+    }
+}
+
+impl PolitenessBonuses {
+    pub fn bonus(&self, marker: Marker) -> f64 {
+        match marker {
+            Marker::Please => self.please,
+            Marker::Thanks => self.thanks,
+            Marker::Formal => self.formal,
+            Marker::Name => self.name,
+        }
+    }
+}
+
+// every kind of manners in the line, each counted once however often it comes up,
+// `names` are whatever the neighbor answers to
+pub fn detect(text: &str, breakdown: &SentimentBreakdown, names: &[String]) -> Vec<Marker> {
+    let words = &breakdown.words;
+    let mut markers = Vec::new();
+    if PLEASE.iter().any(|please| mentions(words, please)) {
+        markers.push(Marker::Please);
+    }
+    if THANKS.iter().any(|thanks| mentions(words, thanks)) {
+        markers.push(Marker::Thanks);
+    }
+    if FORMAL.iter().any(|formal| mentions(words, formal)) {
+        markers.push(Marker::Formal);
+    }
+    let addressed = addressed(text);
+    if names.iter().flat_map(|name| simplify(name)).any(|name| addressed.contains(&name)) {
+        markers.push(Marker::Name);
+    }
+    markers
+}
+
+// people put the name of who they're talking to at the start or the end of a sentence or next to a comma,
+// "Gloria, could you..." and "thanks, Mrs. Brown", anywhere else it's just a word like in "the brown fence"
+fn addressed(text: &str) -> Vec<String> {
+    let titles: Vec<String> = TITLES.iter().flat_map(|title| simplify(title)).collect();
+    let mut addressed = Vec::new();
+    for piece in segment::split(text).into_iter().flat_map(|sentence| sentence.split(',')) {
+        let words: Vec<String> = piece.split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .map(|word| simplify(word).join(" "))
+            .skip_while(|word| titles.contains(word))
+            .collect();
+        addressed.extend(words.first().cloned());
+        addressed.extend(words.last().cloned());
+    }
+    addressed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::score_utterance;
+
+    #[test]
+    fn manners_and_names_are_noticed() {
+        let names = vec![String::from("Gloria"), String::from("Brown")];
+        let markers = |text: &str| detect(text, &score_utterance(text), &names);
+        assert_eq!(markers("Thanks, Gloria!"), vec![Marker::Thanks, Marker::Name]);
+        assert_eq!(markers("could you please, please stop"), vec![Marker::Please]);
+        assert_eq!(markers("Excuse me, Mrs. Brown"), vec![Marker::Formal, Marker::Name]);
+        assert_eq!(markers("move it"), Vec::<Marker>::new());
+        assert_eq!(markers("Mrs. Brown could you move it"), vec![Marker::Formal, Marker::Name]);
+        // a surname that's also a word only counts when it's said to someone
+        assert_eq!(markers("the brown fence is yours"), Vec::<Marker>::new());
+        assert_eq!(markers("move it please, Brown"), vec![Marker::Please, Marker::Name]);
+        assert_eq!(markers("Hi Gloria. Lovely day"), vec![Marker::Name]);
+        assert_eq!(markers("Gloria! Your roses look great"), vec![Marker::Name]);
+        assert_eq!(PolitenessBonuses::default().bonus(Marker::Name), 1.5);
+    }
+}
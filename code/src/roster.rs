@@ -0,0 +1,247 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::emotion::{self, Emotion, Mood};
+use crate::gibberish::Confusion;
+use crate::intent::{self, Intent};
+use crate::politeness::PolitenessBonuses;
+use crate::session::ReplyKind;
+use crate::topic::Topic;
+
+// every neighbor needs enough replies that a whole conversation never repeats one
+pub const MIN_REPLIES: usize = 6;
+// for neighbors that don't have their own way of saying it
+pub const DEFAULT_REPEAT_REPLIES: [&str; 3] = ["You already said that.", "Didn't you just say that?",
+    "Yes, I heard you the first time."];
+pub const DEFAULT_HUH_REPLIES: [&str; 3] = ["Huh? What did you say?", "Sorry, what was that?", "I didn't catch that."];
+
+// how much patience a neighbor starts the conversation with
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Tolerance {
+    Fixed(f64),
+    Random { min: f64, max: f64 },  // used by the bonus rounds
+}
+
+impl Tolerance {
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> f64 {
+        match self {
+            Tolerance::Fixed(value) => *value,
+            Tolerance::Random { min, max } => rng.gen_range(*min..*max),
+        }
+    }
+}
+
+// one neighbor as written in assets/neighbors.json
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NeighborDef {
+    pub id: String,
+    pub sprite: String,
+    pub tolerance: Tolerance,
+    pub name: String,
+    pub age: i8,
+    pub job: String,
+    pub description: String,
+    pub opening_line: String,
+    pub nice_replies: Vec<String>,
+    pub mean_replies: Vec<String>,
+    #[serde(default)]
+    pub repeat_replies: Vec<String>,    // for when the player says the same thing again
+    #[serde(default)]
+    pub huh_replies: Vec<String>,       // for when they can't make out what the player said
+    #[serde(default)]
+    pub confusion: Confusion,           // what they can't make out and if it costs the player a turn
+    #[serde(default)]
+    pub lexicon: Option<String>,    // extra words only this neighbor cares about, see assets/lexicons
+    #[serde(default)]
+    pub topic: Option<Topic>,       // what they came over about, lines that ignore it slowly cost tolerance
+    #[serde(default)]
+    pub intents: HashMap<Intent, f64>,  // how they take apologies, offers and the rest, anything left out uses the default
+    #[serde(default)]
+    pub politeness: PolitenessBonuses,
+    #[serde(default)]
+    pub emotions: HashMap<Emotion, f64>,    // what their mood is worth to them, anything left out uses the default
+}
+
+impl NeighborDef {
+    pub fn intent_weight(&self, intent: Intent) -> f64 {
+        self.intents.get(&intent).copied().unwrap_or_else(|| intent::default_weight(intent))
+    }
+
+    // how a mood sits with this neighbor, added to the line's score
+    pub fn mood_score(&self, mood: &Mood) -> f64 {
+        emotion::ALL.iter()
+            .map(|emotion| mood.get(*emotion) * self.emotions.get(emotion).copied().unwrap_or_else(|| emotion::default_weight(*emotion)))
+            .sum()
+    }
+
+    pub fn replies(&self, kind: ReplyKind) -> &[String] {
+        match kind {
+            ReplyKind::Nice => &self.nice_replies,
+            ReplyKind::Mean => &self.mean_replies,
+            ReplyKind::Repeat => &self.repeat_replies,
+            ReplyKind::Huh => &self.huh_replies,
+        }
+    }
+
+    // their first and last name and the nickname their id starts with, like "cathy"
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.name.split_whitespace().map(String::from).collect();
+        if let Some(nickname) = self.id.split('_').next() {
+            names.push(nickname.to_string());
+        }
+        names
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RosterFile {
+    neighbors: Vec<NeighborDef>,
+}
+
+#[derive(Debug)]
+pub enum RosterError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, source: serde_json::Error },
+    Empty,
+    DuplicateId(String),
+    MissingSprite { id: String, path: PathBuf },
+    BadTolerance(String),
+    EmptyTopic(String),
+    TooFewReplies { id: String, kind: &'static str, found: usize },
+}
+
+impl fmt::Display for RosterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RosterError::Io { path, source } =>
+                write!(f, "could not read {}: {}", path.display(), source),
+            RosterError::Parse { path, source } =>
+                write!(f, "{} is not a valid neighbor roster: {}", path.display(), source),
+            RosterError::Empty =>
+                write!(f, "the neighbor roster has no neighbors"),
+            RosterError::DuplicateId(id) =>
+                write!(f, "neighbor id \"{}\" is used more than once", id),
+            RosterError::MissingSprite { id, path } =>
+                write!(f, "neighbor \"{}\" uses sprite {} which does not exist", id, path.display()),
+            RosterError::BadTolerance(id) =>
+                write!(f, "neighbor \"{}\" has a random tolerance where min is not below max", id),
+            RosterError::EmptyTopic(id) =>
+                write!(f, "neighbor \"{}\" has a topic with no keywords", id),
+            RosterError::TooFewReplies { id, kind, found } =>
+                write!(f, "neighbor \"{}\" has {} {} replies, needs at least {}", id, found, kind, MIN_REPLIES),
+        }
+    }
+}
+
+impl std::error::Error for RosterError {}
+
+// all of the neighbors the player can run into, validated when the game starts
+pub struct NeighborRoster {
+    neighbors: Vec<NeighborDef>,
+}
+
+impl NeighborRoster {
+    // reads the roster at `path` and checks every sprite exists under `asset_dir`
+    pub fn load(path: &Path, asset_dir: &Path) -> Result<Self, RosterError> {
+        let json = fs::read_to_string(path).map_err(|source| RosterError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let roster = Self::from_json(&json).map_err(|source| RosterError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        roster.validate(asset_dir)?;
+        Ok(roster)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut file: RosterFile = serde_json::from_str(json)?;
+        for neighbor in &mut file.neighbors {
+            for (replies, defaults) in [(&mut neighbor.repeat_replies, &DEFAULT_REPEAT_REPLIES),
+                (&mut neighbor.huh_replies, &DEFAULT_HUH_REPLIES)] {
+                if replies.is_empty() {
+                    *replies = defaults.iter().map(|reply| reply.to_string()).collect();
+                }
+            }
+        }
+        Ok(NeighborRoster { neighbors: file.neighbors })
+    }
+
+    pub fn validate(&self, asset_dir: &Path) -> Result<(), RosterError> {
+        if self.neighbors.is_empty() {
+            return Err(RosterError::Empty);
+        }
+        let mut seen = HashSet::new();
+        for neighbor in &self.neighbors {
+            if !seen.insert(neighbor.id.as_str()) {
+                return Err(RosterError::DuplicateId(neighbor.id.clone()));
+            }
+            let sprite = asset_dir.join(&neighbor.sprite);
+            if !sprite.is_file() {
+                return Err(RosterError::MissingSprite { id: neighbor.id.clone(), path: sprite });
+            }
+            if let Tolerance::Random { min, max } = neighbor.tolerance {
+                // a NaN or infinite bound can't be rolled either
+                if !min.is_finite() || !max.is_finite() || min >= max {
+                    return Err(RosterError::BadTolerance(neighbor.id.clone()));
+                }
+            }
+            if neighbor.topic.as_ref().is_some_and(|topic| topic.keywords.is_empty()) {
+                return Err(RosterError::EmptyTopic(neighbor.id.clone()));
+            }
+            for (kind, replies) in [("nice", &neighbor.nice_replies), ("mean", &neighbor.mean_replies)] {
+                if replies.len() < MIN_REPLIES {
+                    return Err(RosterError::TooFewReplies {
+                        id: neighbor.id.clone(),
+                        kind,
+                        found: replies.len(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&NeighborDef> {
+        self.neighbors.iter().find(|neighbor| neighbor.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NeighborDef> {
+        self.neighbors.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets")
+    }
+
+    #[test]
+    fn shipped_roster_is_valid() {
+        let roster = NeighborRoster::load(&asset_dir().join("neighbors.json"), &asset_dir()).unwrap();
+        assert_eq!(roster.get("cathy").unwrap().name, "Catherine Robinson");
+    }
+
+    #[test]
+    fn missing_field_and_sprite_are_reported() {
+        let err = NeighborRoster::from_json(r#"{"neighbors": [{"id": "bob"}]}"#).err().unwrap();
+        assert!(err.to_string().contains("missing field"));
+
+        let mut roster = NeighborRoster::load(&asset_dir().join("neighbors.json"), &asset_dir()).unwrap();
+        roster.neighbors[0].sprite = String::from("nobody.png");
+        let err = roster.validate(&asset_dir()).unwrap_err();
+        assert!(err.to_string().contains("nobody.png"));
+    }
+}
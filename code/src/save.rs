@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::campaign::LevelId;
+
+// bump this when SaveGame changes in a way a #[serde(default)] field can't cover, and have `load` migrate the older layout,
+// `load` refuses files from a newer version of the game
+pub const SAVE_VERSION: u32 = 1;
+
+const SAVE_DIR: &str = "suburban-rumble";
+const SAVE_FILE: &str = "save.json";
+
+// how the player got past a neighbor
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Talked,
+    Fought,
+}
+
+// player preferences, every field needs a default so older saves still load
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub vsync: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings { vsync: true }
+    }
+}
+
+// everything we keep between runs of the game
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SaveGame {
+    pub version: u32,
+    pub level: Option<LevelId>,     // None once the whole campaign has been beaten
+    pub outcomes: BTreeMap<LevelId, Outcome>,
+    #[serde(default)]
+    pub settings: Settings,
+}
+
+impl Default for SaveGame {
+    fn default() -> Self {
+        SaveGame {
+            version: SAVE_VERSION,
+            level: None,
+            outcomes: BTreeMap::new(),
+            settings: Settings::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    NoDataDir,
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, source: serde_json::Error },
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::NoDataDir =>
+                write!(f, "this system has no user data directory"),
+            SaveError::Io { path, source } =>
+                write!(f, "could not access {}: {}", path.display(), source),
+            SaveError::Parse { path, source } =>
+                write!(f, "{} is not a valid save file: {}", path.display(), source),
+            SaveError::UnsupportedVersion(version) =>
+                write!(f, "save file version {} is newer than this game (version {})", version, SAVE_VERSION),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+#[derive(Deserialize)]
+struct VersionOnly {
+    version: u32,
+}
+
+impl SaveGame {
+    // where the save lives, e.g. ~/.local/share/suburban-rumble/save.json on linux
+    pub fn default_path() -> Result<PathBuf, SaveError> {
+        dirs::data_dir()
+            .map(|dir| dir.join(SAVE_DIR).join(SAVE_FILE))
+            .ok_or(SaveError::NoDataDir)
+    }
+
+    // a missing file is not an error, it just means a fresh game
+    pub fn load(path: &Path) -> Result<Self, SaveError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SaveGame::default()),
+            Err(source) => return Err(SaveError::Io { path: path.to_path_buf(), source }),
+        };
+        let parse_err = |source| SaveError::Parse { path: path.to_path_buf(), source };
+        let VersionOnly { version } = serde_json::from_str(&json).map_err(parse_err)?;
+        if version > SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion(version));
+        }
+        let mut save: SaveGame = serde_json::from_str(&json).map_err(parse_err)?;
+        save.version = SAVE_VERSION;
+        Ok(save)
+    }
+
+    // writes to a temporary file first so a crash mid-write can't eat the old save
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        let io_err = |source| SaveError::Io { path: path.to_path_buf(), source };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        let json = serde_json::to_string_pretty(self).expect("a save game always serializes");
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json).map_err(io_err)?;
+        fs::rename(&tmp, path).map_err(io_err)
+    }
+
+    pub fn has_progress(&self) -> bool {
+        self.level.is_some()
+    }
+
+    // forget the old campaign but keep the player's settings
+    pub fn start_new(&mut self, first_level: &LevelId) {
+        self.level = Some(first_level.clone());
+        self.outcomes.clear();
+    }
+
+    pub fn record(&mut self, level: &LevelId, outcome: Outcome) {
+        self.outcomes.insert(level.clone(), outcome);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_round_trips_and_rejects_newer_versions() {
+        let dir = std::env::temp_dir().join(format!("suburban-rumble-test-{}", std::process::id()));
+        let path = dir.join(SAVE_FILE);
+        assert_eq!(SaveGame::load(&path).unwrap(), SaveGame::default());
+
+        let mut save = SaveGame::default();
+        save.start_new(&LevelId(String::from("cathy")));
+        save.record(&LevelId(String::from("cathy")), Outcome::Fought);
+        save.level = Some(LevelId(String::from("billy")));
+        save.save(&path).unwrap();
+        assert_eq!(SaveGame::load(&path).unwrap(), save);
+
+        // a version 1 save as the first release of the save game wrote it
+        fs::write(&path, r#"{
+  "version": 1,
+  "level": "billy",
+  "outcomes": {
+    "cathy": "talked"
+  },
+  "settings": {
+    "vsync": false
+  }
+}"#).unwrap();
+        let loaded = SaveGame::load(&path).unwrap();
+        assert_eq!(loaded.level, Some(LevelId(String::from("billy"))));
+        assert_eq!(loaded.outcomes[&LevelId(String::from("cathy"))], Outcome::Talked);
+        assert!(!loaded.settings.vsync);
+
+        fs::write(&path, r#"{"version": 99}"#).unwrap();
+        assert!(matches!(SaveGame::load(&path), Err(SaveError::UnsupportedVersion(99))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use rand::Rng;
+
+use crate::emotion::{self, Mood};
+use crate::gibberish::Nonsense;
+use crate::intent::{self, Intent};
+use crate::politeness::{self, Marker};
+use crate::roster::NeighborDef;
+use crate::sentiment::lexicon::Lexicon;
+use crate::sentiment::classifier::Scorer;
+use crate::sentiment::SentimentBreakdown;
+use crate::session::{ConversationSession, Repetition, Speaker, TurnResult};
+use crate::topic::Relevance;
+
+// everything that went into what one line did to a neighbor's tolerance
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineScore {
+    pub sentiment: SentimentBreakdown,
+    pub nonsense: Option<Nonsense>,     // the neighbor couldn't make it out, nothing else was scored
+    pub relevance: Option<Relevance>,   // none if the neighbor has no topic
+    pub intents: Vec<(Intent, f64)>,    // what the line was trying to do and what this neighbor thought of it
+    pub politeness: Vec<(Marker, f64)>, // manners, and the bonus this neighbor gives for each
+    pub repetition: Repetition,
+    pub feeling: Mood,                  // what this line on its own made the neighbor feel
+    pub mood: Mood,                     // how they feel after it, with what's left of earlier lines
+    pub mood_score: f64,                // what that mood is worth to this neighbor
+    pub total: f64,                     // what to take the turn with
+}
+
+// scores a line from the player the way `neighbor` hears it,
+// the session keeps whatever has to carry over from one line to the next
+pub fn score_line(text: &str, neighbor: &NeighborDef, lexicon: &dyn Lexicon, scorer: &Scorer,
+    session: &mut ConversationSession) -> LineScore {
+    let sentiment = scorer.score(text, lexicon);
+    // mumbling doesn't count as going off topic or as saying something again
+    let nonsense = neighbor.confusion.check(text, &sentiment);
+    if nonsense.is_some() {
+        let repetition = Repetition { times: 0, overlap: 0., factor: 1. };
+        return LineScore { sentiment, nonsense, relevance: None, intents: Vec::new(), politeness: Vec::new(), repetition,
+            feeling: Mood::default(), mood: session.mood(), mood_score: 0., total: 0. };
+    }
+    let relevance = neighbor.topic.as_ref().map(|topic| session.weigh_topic(topic, &sentiment));
+    let repetition = session.weigh_repetition(&sentiment.words);
+    let intents: Vec<(Intent, f64)> = intent::detect(text, &sentiment)
+        .into_iter()
+        .map(|intent| (intent, neighbor.intent_weight(intent)))
+        .collect();
+    let politeness: Vec<(Marker, f64)> = politeness::detect(text, &sentiment, &neighbor.names())
+        .into_iter()
+        .map(|marker| (marker, neighbor.politeness.bonus(marker)))
+        .collect();
+    // a neighbor who was made angry a line ago is still a bit angry now
+    let feeling = emotion::feel(&sentiment);
+    let mood = session.feel(&feeling);
+    let mood_score = neighbor.mood_score(&mood);
+
+    let total = sentiment.score
+        + relevance.as_ref().map_or(0., |relevance| relevance.score)
+        + intents.iter().map(|(_, weight)| weight).sum::<f64>()
+        + politeness.iter().map(|(_, bonus)| bonus).sum::<f64>()
+        + mood_score;
+    // saying something nice again is worth less every time, saying something mean again isn't any cheaper
+    let total = if total > 0. { total * repetition.factor } else { total };
+    LineScore { sentiment, nonsense, relevance, intents, politeness, repetition, feeling, mood, mood_score, total }
+}
+
+// what a neighbor said back to one line from the player, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub line: LineScore,
+    pub turn: TurnResult,
+    pub reply: usize,   // which of the neighbor's replies of `turn.reply`'s kind they answered with
+}
+
+// one whole exchange: scores the line, takes the turn with it and picks a reply that hasn't been used yet,
+// the line and the reply both go in the session's transcript, the game and the simulator both go through here
+pub fn respond<R: Rng>(text: &str, neighbor: &NeighborDef, lexicon: &dyn Lexicon, scorer: &Scorer,
+    session: &mut ConversationSession, rng: &mut R) -> Response {
+    let line = score_line(text, neighbor, lexicon, scorer, session);
+    session.record_scored(Speaker::Player, text, line.sentiment.sentences.clone());
+    // empty lines and keyboard mashing get a "huh?" instead of a free point, and usually don't use up a turn
+    let turn = match line.nonsense {
+        Some(_) => session.take_confused_turn(neighbor.confusion.costs_turn),
+        None => session.take_turn(line.total),
+    };
+    let replies = neighbor.replies(turn.reply);
+    let reply = session.pick_reply(turn.reply, replies.len(), rng);
+    session.record(Speaker::Neighbor, &replies[reply]);
+    Response { line, turn, reply }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::roster::NeighborRoster;
+    use crate::sentiment::afinn::Afinn111;
+    use crate::session::ReplyKind;
+
+    #[test]
+    fn neighbors_weigh_intents_their_own_way() {
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let roster = NeighborRoster::load(&asset_dir.join("neighbors.json"), &asset_dir).unwrap();
+        let line = "I'll move my car";
+        let jeffrey = score_line(line, roster.get("jeffrey").unwrap(), &Afinn111, &Scorer::default(), &mut ConversationSession::new(10., 4));
+        let karen = score_line(line, roster.get("karen").unwrap(), &Afinn111, &Scorer::default(), &mut ConversationSession::new(10., 4));
+        assert_eq!(jeffrey.intents, vec![(Intent::Offer, 6.)]);
+        assert!(jeffrey.total > karen.total);
+
+        // karen wants to be spoken to properly, billy couldn't care less
+        let line = "Excuse me, Mrs. Martinez";
+        let karen = score_line(line, roster.get("karen").unwrap(), &Afinn111, &Scorer::default(), &mut ConversationSession::new(10., 4));
+        assert_eq!(karen.politeness, vec![(Marker::Formal, 3.), (Marker::Name, 2.)]);
+        let line = "Excuse me, Billy";
+        let billy = score_line(line, roster.get("billy").unwrap(), &Afinn111, &Scorer::default(), &mut ConversationSession::new(10., 4));
+        assert_eq!(billy.politeness, vec![(Marker::Formal, -1.), (Marker::Name, 0.)]);
+    }
+
+    #[test]
+    fn a_response_is_recorded_with_its_reply() {
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let roster = NeighborRoster::load(&asset_dir.join("neighbors.json"), &asset_dir).unwrap();
+        let cathy = roster.get("cathy").unwrap();
+        let mut session = ConversationSession::new(10., 4);
+        let response = respond("you're wonderful", cathy, &Afinn111, &Scorer::default(), &mut session, &mut rand::thread_rng());
+        assert_eq!(response.turn.reply, ReplyKind::Nice);
+        let said: Vec<&str> = session.transcript().iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(said, vec!["you're wonderful", cathy.nice_replies[response.reply].as_str()]);
+    }
+}
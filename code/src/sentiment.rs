@@ -0,0 +1,375 @@
+use rust_stemmers::{Algorithm, Stemmer};
+
+pub mod afinn;
+pub mod classifier;
+pub mod emoticons;
+pub mod fuzzy;
+pub mod lexicon;
+pub mod normalize;
+pub mod segment;
+pub mod tokenizer;
+
+use afinn::Afinn111;
+use emoticons::Emoticons;
+use lexicon::{Lexicon, StackedLexicon};
+use segment::SentenceScore;
+pub use tokenizer::{tokenize, Token};
+
+// these are compared against stemmed words, which is why the emphasizers look misspelled,
+// any other word ending in n't is a negator too
+pub const NEGATOR_WORDS: [&str; 8] = ["not", "don't", "dont", "neither", "never", "seldom", "nevermore", "little"];
+pub const EMPHASIZING_WORDS: [&str; 17] = ["veri", "pretti", "extrem", "vast", "huge", "especi", "over",
+    "exceed", "extra", "immens", "tremend", "excess", "great", "genuin", "realli", "super", "truli"];
+
+// a negator reaches this many words ahead of it, but never past the end of its clause
+const NEGATION_WINDOW: usize = 3;
+// what a negated word is multiplied by, less than a full flip since "not bad" isn't as nice as "good"
+const NEGATION_SCALAR: f64 = -0.74;
+const CLAUSE_WORDS: [&str; 2] = ["but", "and"];
+
+// these turn the next scored word down instead of up, compared against the words as typed
+const DOWNTONERS: [&str; 11] = ["slightly", "somewhat", "barely", "marginally", "partly", "fairly", "mildly",
+    "kinda", "sorta", "kind of", "sort of"];
+const DOWNTONER_SCALAR: f64 = 0.5;
+// a scored word in capitals when the rest of the line isn't
+const CAPS_SCALAR: f64 = 1.5;
+// "but" makes the clause before it matter less and the one after it more
+const BEFORE_BUT_SCALAR: f64 = 0.5;
+const AFTER_BUT_SCALAR: f64 = 1.5;
+// every "!" adds this much to the line, only the first few count
+const EXCLAMATION_BOOST: f64 = 0.15;
+const MAX_EXCLAMATIONS: usize = 4;
+
+// one of the rules that changed how much a line was worth
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Negation,
+    Downtoner,
+    Caps,
+    Contrast,
+    Emphasis,
+    Elongation,
+    Exclamation,
+    Typo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleEffect {
+    pub rule: Rule,
+    pub word: Option<String>,   // the matched word it applied to, none if it applied to the whole line
+    pub factor: f64,
+}
+
+// everything that went into scoring one line from the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentimentBreakdown {
+    pub heard: String,                  // the line after chat shorthand and stretched letters were undone
+    pub words: Vec<String>,             // the simplified sentence that was looked up
+    pub matched: Vec<(String, f64)>,    // words the lexicon knows and what they were worth
+    pub rules: Vec<RuleEffect>,         // every rule that scaled a word or the whole line, in order
+    pub corrected: Vec<(String, String)>,   // typos and the lexicon word they were read as
+    pub unknown: Vec<String>,           // words that had no score and weren't negators or emphasizers
+    pub positive_score: f64,
+    pub negative_score: f64,
+    pub multiplier: f64,
+    pub score: f64,                     // what the line does to the neighbor's tolerance
+    pub classified: Option<f64>,        // what the naive bayes model thought, if the scorer uses it
+    pub sentences: Vec<SentenceScore>,  // how each sentence scored when there was more than one, `score` is their weighted average
+}
+
+impl SentimentBreakdown {
+    // no lexicon word in it and nothing the classifier thought either way
+    pub fn is_neutral(&self) -> bool {
+        self.matched.is_empty() && self.classified.is_none_or(|classified| classified == 0.)
+    }
+}
+
+pub fn simplify(text: &str) -> Vec<String> {
+    tokenize(text).into_iter().map(|token| token.stem).collect()
+}
+
+// true if the simplified words of `phrase` show up in a row somewhere in `words`
+pub fn mentions(words: &[String], phrase: &str) -> bool {
+    let phrase = simplify(phrase);
+    !phrase.is_empty() && words.windows(phrase.len()).any(|window| window == phrase.as_slice())
+}
+
+pub(crate) fn simplify_word(stemmer: &Stemmer, word: &str) -> String {
+    //stemmer changes words that end with y to end in i instead, the dictionary doesn't have use for those words so
+    //we make an exception here
+    if word.ends_with('y') {
+        word.to_string()
+    } else {
+        stemmer.stem(word).into_owned()
+    }
+}
+
+pub(crate) fn is_negator(word: &str) -> bool {
+    NEGATOR_WORDS.contains(&word) || word.ends_with("n't")
+}
+
+// words ending in y skipped the stemmer in `simplify`, so stem them here before comparing
+fn is_emphasizer(stemmer: &Stemmer, word: &str) -> bool {
+    EMPHASIZING_WORDS.contains(&stemmer.stem(word).as_ref())
+}
+
+fn is_downtoner(word: &str) -> bool {
+    DOWNTONERS.contains(&word)
+}
+
+// negators, emphasizers and downtoners change the words around them instead of scoring themselves
+pub(crate) fn is_modifier(stemmer: &Stemmer, token: &Token) -> bool {
+    is_negator(&token.stem) || is_emphasizer(stemmer, &token.stem) || is_downtoner(&token.word)
+}
+
+// how many negators and downtoners are in the few words before token `start`, stopping at the start of its clause
+fn modifiers_before(tokens: &[Token], start: usize, consumed: impl Fn(usize) -> bool) -> (i32, i32) {
+    let mut negations = 0;
+    let mut downtoners = 0;
+    for index in (start.saturating_sub(NEGATION_WINDOW)..start).rev() {
+        let token = &tokens[index];
+        if token.ends_clause || CLAUSE_WORDS.contains(&token.word.as_str()) {
+            break;
+        }
+        if consumed(index) {
+            continue;
+        }
+        if is_negator(&token.stem) {
+            negations += 1;
+        } else if is_downtoner(&token.word) {
+            downtoners += 1;
+        }
+    }
+    (negations, downtoners)
+}
+
+// for lines with no scored words: every negator flips the sign of the line, every emphasizer doubles it
+pub fn multiplier(simple_sentence: &[String]) -> f64 {
+    let stemmer = Stemmer::create(Algorithm::English);
+    let mut multiplier = 1.0;
+    for word in simple_sentence {
+        if is_negator(word) {
+            multiplier *= -1.0;
+        } else if is_emphasizer(&stemmer, word) {
+            multiplier *= 2.0;
+        }
+    }
+    multiplier
+}
+
+// scores one line from the player against the bundled AFINN-111 list and emoticon table
+pub fn score_utterance(text: &str) -> SentimentBreakdown {
+    let mut builtin = StackedLexicon::default();
+    builtin.push(Afinn111);
+    builtin.push(Emoticons);
+    score_utterance_with(text, &builtin)
+}
+
+// every scored word is negated, toned down, shouted or weighed against a "but" on its own,
+// emphasizers, stretched out words and exclamation marks scale the whole line,
+// a line with no scored words in it is worth the multiplier on its own,
+// words that were part of a phrase don't count as negators or emphasizers a second time
+pub fn score_utterance_with(text: &str, lexicon: &dyn Lexicon) -> SentimentBreakdown {
+    let stemmer = Stemmer::create(Algorithm::English);
+    let mut tokens = tokenize(text);
+    normalize::restore_doubles(&mut tokens, lexicon);
+    let affin = afinn::generate_affin_scores(&tokens, lexicon);
+    // capitals only stand out if the whole line isn't in capitals
+    let all_shouted = tokens.iter().all(|token| token.shouted);
+    let but = tokens.iter().position(|token| token.word == "but");
+
+    let mut net_score = 0.;
+    let mut rules = Vec::new();
+    for hit in &affin.matched {
+        let (negations, downtoners) = modifiers_before(&tokens, hit.start, |index| affin.consumed(index));
+        let mut word_rules = Vec::new();
+        if hit.confidence < 1. {
+            word_rules.push((Rule::Typo, hit.confidence));
+        }
+        if negations > 0 {
+            word_rules.push((Rule::Negation, NEGATION_SCALAR.powi(negations)));
+        }
+        if downtoners > 0 {
+            word_rules.push((Rule::Downtoner, DOWNTONER_SCALAR.powi(downtoners)));
+        }
+        if !all_shouted && tokens[hit.start..hit.start + hit.len].iter().any(|token| token.shouted) {
+            word_rules.push((Rule::Caps, CAPS_SCALAR));
+        }
+        match but {
+            Some(but) if hit.start < but => word_rules.push((Rule::Contrast, BEFORE_BUT_SCALAR)),
+            Some(but) if hit.start > but => word_rules.push((Rule::Contrast, AFTER_BUT_SCALAR)),
+            _ => (),
+        }
+
+        let mut value = hit.value;
+        for (rule, factor) in word_rules {
+            value *= factor;
+            rules.push(RuleEffect { rule, word: Some(hit.text.clone()), factor });
+        }
+        net_score += value;
+    }
+
+    let leftover: Vec<&Token> = tokens.iter()
+        .enumerate()
+        .filter(|(index, _)| !affin.consumed(*index))
+        .map(|(_, token)| token)
+        .collect();
+    let (multiplier, score) = if net_score == 0.0 {
+        let stems: Vec<String> = leftover.iter().map(|token| token.stem.clone()).collect();
+        let multiplier = multiplier(&stems);
+        (multiplier, multiplier)
+    } else {
+        let emphasis = leftover.iter().filter(|token| is_emphasizer(&stemmer, &token.stem)).count();
+        let elongated = tokens.iter().filter(|token| token.elongated).count();
+        let exclamations = text.matches('!').count().min(MAX_EXCLAMATIONS);
+        let mut multiplier = 1.;
+        if emphasis > 0 {
+            let factor = 2f64.powi(emphasis as i32);
+            multiplier *= factor;
+            rules.push(RuleEffect { rule: Rule::Emphasis, word: None, factor });
+        }
+        // "soooo nice" is meant as much as "very nice"
+        if elongated > 0 {
+            let factor = 2f64.powi(elongated as i32);
+            multiplier *= factor;
+            rules.push(RuleEffect { rule: Rule::Elongation, word: None, factor });
+        }
+        if exclamations > 0 {
+            let factor = 1. + EXCLAMATION_BOOST * exclamations as f64;
+            multiplier *= factor;
+            rules.push(RuleEffect { rule: Rule::Exclamation, word: None, factor });
+        }
+        (multiplier, net_score * multiplier)
+    };
+    let unknown = leftover.into_iter()
+        .filter(|token| !is_modifier(&stemmer, token))
+        .map(|token| token.stem.clone())
+        .collect();
+    let corrected = affin.matched.iter()
+        .filter_map(|hit| hit.corrected_from.clone().map(|typed| (typed, hit.text.clone())))
+        .collect();
+    let heard = tokens.iter().map(|token| token.word.as_str()).collect::<Vec<_>>().join(" ");
+    let words = tokens.into_iter().map(|token| token.stem).collect();
+    SentimentBreakdown {
+        heard,
+        words,
+        matched: affin.matched.into_iter().map(|hit| (hit.text, hit.value)).collect(),
+        rules,
+        corrected,
+        unknown,
+        positive_score: affin.positive_score,
+        negative_score: affin.negative_score,
+        multiplier,
+        score,
+        classified: None,
+        sentences: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} is not {}", actual, expected);
+    }
+
+    #[test]
+    fn negators_only_reach_the_next_few_words() {
+        assert_eq!(score_utterance("that is bad").score, -3.);
+        assert_close(score_utterance("that is not bad").score, 2.22);
+        assert_close(score_utterance("I don't never hate you").score, -3. * 0.74 * 0.74);
+        assert_close(score_utterance("it isn’t bad").score, 2.22);
+        // too far away from "bad" to count
+        assert_eq!(score_utterance("not that this was ever bad").score, -3.);
+    }
+
+    #[test]
+    fn negation_stops_at_the_end_of_a_clause() {
+        let breakdown = score_utterance("I don't hate you, you are wonderful");
+        assert_eq!(breakdown.rules, vec![RuleEffect { rule: Rule::Negation, word: Some(String::from("hate")), factor: -0.74 }]);
+        assert_close(breakdown.score, 2.22 + 4.);
+        assert_close(score_utterance("I don't hate you but I hate this").score, 2.22 * 0.5 - 3. * 1.5);
+        assert_eq!(score_utterance("not , bad").score, -3.);
+        // nothing to negate, so the old whole line flip still applies
+        assert_eq!(score_utterance("I don't know").score, -1.);
+    }
+
+    #[test]
+    fn emphasizers_double_the_score() {
+        let breakdown = score_utterance("you are very very nice");
+        assert_eq!(breakdown.multiplier, 4.);
+        assert_eq!(breakdown.score, 12.);
+        assert_eq!(score_utterance("extremely bad").score, -6.);
+    }
+
+    #[test]
+    fn intensity_rules_scale_the_score() {
+        assert_close(score_utterance("that is BAD").score, -4.5);
+        assert_eq!(score_utterance("THAT IS BAD").score, -3.);
+        assert_close(score_utterance("that is bad!!!").score, -3. * 1.45);
+        assert_close(score_utterance("that is bad!!!!!!!!").score, -3. * 1.6);
+        assert_close(score_utterance("that is slightly bad").score, -1.5);
+
+        // "kind" is nice on its own, but not here
+        let breakdown = score_utterance("you're kind of stupid but I like you");
+        assert_eq!(breakdown.matched, vec![(String::from("stupid"), -2.), (String::from("like"), 2.)]);
+        let rules: Vec<Rule> = breakdown.rules.iter().map(|effect| effect.rule).collect();
+        assert_eq!(rules, vec![Rule::Downtoner, Rule::Contrast, Rule::Contrast]);
+        assert_close(breakdown.score, -2. * 0.5 * 0.5 + 2. * 1.5);
+        assert!(breakdown.unknown.iter().all(|word| word != "kind of"));
+    }
+
+    #[test]
+    fn emoticons_and_emoji_score() {
+        assert_eq!(score_utterance("thanks :)").score, 4.);
+        assert_eq!(score_utterance("\u{1f595}").score, -5.);
+        // the heart comes with an emoji style selector and the thumb with a skin tone
+        assert_eq!(score_utterance("\u{2764}\u{fe0f}").score, 3.);
+        assert_eq!(score_utterance("\u{1f44d}\u{1f3fd}").matched, vec![(String::from("\u{1f44d}"), 2.)]);
+        assert_close(score_utterance("not :(").score, 1.48);
+    }
+
+    #[test]
+    fn stretched_words_count_as_emphasis() {
+        let breakdown = score_utterance("soooo niiice");
+        assert_eq!(breakdown.heard, "so nice");
+        // both words were stretched out, like "very very nice"
+        assert_eq!(breakdown.score, 3. * 4.);
+        assert_eq!(score_utterance("u r gr8").score, 3.);
+        assert_eq!(score_utterance("h8 u").score, -3.);
+    }
+
+    #[test]
+    fn punctuation_and_articles_are_ignored() {
+        let breakdown = score_utterance("The cake was GOOD!!! Thanks, neighbor.");
+        assert_eq!(breakdown.words, vec!["cake", "was", "good", "thank", "neighbor"]);
+        assert_eq!(breakdown.matched, vec![(String::from("good"), 3.), (String::from("thanks"), 2.)]);
+        // the capitals and the exclamation marks still count
+        assert_close(breakdown.score, (3. * 1.5 + 2.) * 1.45);
+    }
+
+    #[test]
+    fn phrases_win_over_their_words() {
+        // "not" is part of the phrase so it doesn't flip the line as well
+        let breakdown = score_utterance("that is not good");
+        assert_eq!(breakdown.matched, vec![(String::from("not good"), -2.)]);
+        assert_eq!(breakdown.multiplier, 1.);
+        assert_eq!(breakdown.score, -2.);
+
+        assert_eq!(score_utterance("this does not work").score, -3.);
+        assert_eq!(score_utterance("no fun at all").matched, vec![(String::from("no fun"), -3.)]);
+        // the longest phrase is tried first
+        assert_eq!(score_utterance("you're cashing in").matched, vec![(String::from("cashing in"), -2.)]);
+    }
+
+    #[test]
+    fn unknown_words_score_the_multiplier() {
+        let breakdown = score_utterance("flibbertigibbet");
+        assert_eq!(breakdown.unknown, vec!["flibbertigibbet"]);
+        assert!(breakdown.matched.is_empty());
+        assert_eq!(breakdown.score, 1.);
+        assert_eq!(score_utterance("not flibbertigibbet").score, -1.);
+        assert_eq!(score_utterance("").score, 1.);
+    }
+}
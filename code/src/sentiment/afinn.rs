@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::lexicon::{phrase_length, Lexicon};
+use super::Token;
+
+
+const AFFIN: &[u8; 33050] = include_bytes!("./AFINN-111.json");
+
+// parsed the first time anything is scored, every utterance after that is just hash lookups
+static LEXICON: OnceLock<HashMap<String, i8>> = OnceLock::new();
+static LONGEST_PHRASE: OnceLock<usize> = OnceLock::new();
+
+// the AFINN-111 list that is compiled into the game
+pub struct Afinn111;
+
+impl Lexicon for Afinn111 {
+    fn score(&self, word: &str) -> Option<i8> {
+        lexicon().get(word).copied()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        lexicon().keys().cloned().collect()
+    }
+
+    fn longest_phrase(&self) -> usize {
+        *LONGEST_PHRASE.get_or_init(|| lexicon().keys().map(|key| phrase_length(key)).max().unwrap_or(1))
+    }
+}
+
+// one hit in the lexicon, a single word or a phrase of `len` words starting at token `start`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordMatch {
+    pub start: usize,
+    pub len: usize,
+    pub text: String,
+    pub value: f64,
+    pub corrected_from: Option<String>, // what was typed, if it only matched as a typo of `text`
+    pub confidence: f64,                // 1 unless it was a typo
+}
+
+pub struct SentimentScore {
+        pub positive_score: f64,
+        pub negative_score: f64,
+        pub net_score: f64,
+        pub total_words: f64,
+        pub positive_matched_words: i32,
+        pub negative_matched_words: i32,
+        pub matched: Vec<WordMatch>,    // every word or phrase that had a score, in order
+
+}
+
+impl SentimentScore {
+    // true if the token at `index` was part of a match, so it shouldn't count for anything else
+    pub fn consumed(&self, index: usize) -> bool {
+        self.matched.iter().any(|hit| (hit.start..hit.start + hit.len).contains(&index))
+    }
+}
+
+// looks for the longest phrase the lexicon knows at every position before falling back to the single word,
+// as typed and then stemmed, and only then to whatever word it looks like a typo of,
+// phrases are matched on the words as typed since the lexicons list them that way
+pub fn generate_affin_scores(words: &[Token], affin_values: &dyn Lexicon) -> SentimentScore {
+    let mut positive_score = 0.;
+    let mut positive_words = 0;
+    let mut negative_score = 0.;
+    let mut negative_words = 0;
+    let mut matched = Vec::new();
+
+    let longest_phrase = affin_values.longest_phrase();
+    let mut i = 0;
+    while i < words.len() {
+        let mut hit = None;
+        for len in (2..=longest_phrase.min(words.len() - i)).rev() {
+            let phrase = words[i..i + len].iter().map(|token| token.word.as_str()).collect::<Vec<_>>().join(" ");
+            if let Some(val) = affin_values.score(&phrase) {
+                hit = Some(WordMatch { start: i, len, text: phrase, value: f64::from(val), corrected_from: None, confidence: 1. });
+                break;
+            }
+        }
+        if hit.is_none() {
+            // AFINN lists "wonderful" but not its stem "wonder", the file lexicons are keyed by stems
+            let found = [&words[i].word, &words[i].stem].into_iter()
+                .find_map(|key| affin_values.score(key).map(|val| (key, val)));
+            if let Some((key, val)) = found {
+                hit = Some(WordMatch {
+                    start: i,
+                    len: 1,
+                    text: key.clone(),
+                    value: f64::from(val),
+                    corrected_from: None,
+                    confidence: 1.,
+                });
+            }
+        }
+        if hit.is_none() {
+            if let Some(fix) = affin_values.correct(&words[i]) {
+                if let Some(val) = affin_values.score(&fix.word) {
+                    hit = Some(WordMatch {
+                        start: i,
+                        len: 1,
+                        text: fix.word,
+                        value: f64::from(val),
+                        corrected_from: Some(words[i].word.clone()),
+                        confidence: fix.confidence,
+                    });
+                }
+            }
+        }
+        match hit {
+            Some(hit) => {
+                let affin_val = hit.value;
+                i += hit.len;
+                matched.push(hit);
+                if affin_val > 0. {
+                    positive_score += affin_val;
+                    positive_words+=1;
+                } else if affin_val < 0. {
+                    negative_score += affin_val;
+                    negative_words+=1;
+                }
+            }
+            None => i += 1,
+        }
+    }
+
+    SentimentScore {
+        positive_score,
+        negative_score,
+        net_score: positive_score + negative_score,
+        total_words: words.len() as f64,
+        positive_matched_words: positive_words,
+        negative_matched_words: negative_words,
+        matched,
+    }
+}
+
+pub fn lexicon() -> &'static HashMap<String, i8> {
+    LEXICON.get_or_init(parse_lexicon)
+}
+
+// only public so the benchmark can show what every utterance used to cost
+pub fn parse_lexicon() -> HashMap<String, i8> {
+    serde_json::from_slice(AFFIN).expect("the bundled AFINN-111.json is valid")
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::lexicon::{Lexicon, LexiconError};
+use super::segment;
+use super::{score_utterance_with, simplify, SentimentBreakdown};
+
+// added to every count so a feature one side never saw in training doesn't rule it out completely
+const SMOOTHING: f64 = 1.;
+// what a line the classifier is completely sure about does to tolerance, about a strong lexicon word
+const SCORE_SCALE: f64 = 4.;
+
+// what a line from the labeled corpus is
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Label {
+    Neighborly,
+    Hostile,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub label: Label,
+    pub text: String,
+}
+
+// "label<tab>line" on every line, blank lines and lines starting with # are skipped,
+// the error is the line number that isn't right
+pub fn parse_corpus(text: &str) -> Result<Vec<Example>, usize> {
+    let mut examples = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (label, text) = line.split_once('\t').ok_or(index + 1)?;
+        let label = match label.trim() {
+            "neighborly" => Label::Neighborly,
+            "hostile" => Label::Hostile,
+            _ => return Err(index + 1),
+        };
+        examples.push(Example { label, text: text.trim().to_string() });
+    }
+    Ok(examples)
+}
+
+// the simplified words of a line and every pair of words next to each other,
+// the pairs are what let "not my problem" mean something different from "problem"
+pub fn features(text: &str) -> Vec<String> {
+    let words = simplify(text);
+    let pairs: Vec<String> = words.windows(2).map(|pair| pair.join(" ")).collect();
+    words.into_iter().chain(pairs).collect()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct ClassCounts {
+    label: Label,
+    lines: u32,
+    features: u32,                  // every feature seen in this class, counting repeats
+    counts: BTreeMap<String, u32>,  // sorted so a retrained model only changes where the corpus did
+}
+
+// a bag of words naive bayes model, trained by `cargo run --bin classifier -- train` and saved to assets/classifier.json
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NaiveBayes {
+    classes: Vec<ClassCounts>,
+    vocabulary: usize,
+}
+
+impl NaiveBayes {
+    pub fn train(examples: &[Example]) -> Self {
+        let mut classes: BTreeMap<Label, ClassCounts> = BTreeMap::new();
+        let mut vocabulary = std::collections::HashSet::new();
+        for example in examples {
+            let class = classes.entry(example.label).or_insert_with(|| ClassCounts {
+                label: example.label,
+                lines: 0,
+                features: 0,
+                counts: BTreeMap::new(),
+            });
+            class.lines += 1;
+            for feature in features(&example.text) {
+                class.features += 1;
+                *class.counts.entry(feature.clone()).or_insert(0) += 1;
+                vocabulary.insert(feature);
+            }
+        }
+        NaiveBayes { classes: classes.into_values().collect(), vocabulary: vocabulary.len() }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, LexiconError> {
+        let json = fs::read_to_string(path).map_err(|source| LexiconError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_str(&json).map_err(|source| LexiconError::BadModel {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        fs::write(path, json + "\n")
+    }
+
+    // how likely each label is for the line, adding up to 1
+    pub fn probabilities(&self, text: &str) -> Vec<(Label, f64)> {
+        let features = features(text);
+        let total_lines: u32 = self.classes.iter().map(|class| class.lines).sum();
+        let log_odds: Vec<(Label, f64)> = self.classes.iter()
+            .map(|class| {
+                let prior = (f64::from(class.lines) / f64::from(total_lines)).ln();
+                let denominator = f64::from(class.features) + SMOOTHING * self.vocabulary as f64;
+                let likelihood: f64 = features.iter()
+                    // features nobody has seen say nothing about either label
+                    .filter(|feature| self.classes.iter().any(|class| class.counts.contains_key(*feature)))
+                    .map(|feature| {
+                        let count = class.counts.get(feature).copied().unwrap_or(0);
+                        ((f64::from(count) + SMOOTHING) / denominator).ln()
+                    })
+                    .sum();
+                (class.label, prior + likelihood)
+            })
+            .collect();
+        let best = log_odds.iter().map(|(_, odds)| *odds).fold(f64::NEG_INFINITY, f64::max);
+        let total: f64 = log_odds.iter().map(|(_, odds)| (odds - best).exp()).sum();
+        log_odds.into_iter().map(|(label, odds)| (label, (odds - best).exp() / total)).collect()
+    }
+
+    pub fn classify(&self, text: &str) -> Label {
+        self.probabilities(text)
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(Label::Neighborly, |(label, _)| label)
+    }
+
+    // how neighborly the line sounds on the same scale as the lexicon's score
+    pub fn score(&self, text: &str) -> f64 {
+        let probabilities = self.probabilities(text);
+        let probability = |wanted: Label| {
+            probabilities.iter().find(|(label, _)| *label == wanted).map_or(0., |(_, probability)| *probability)
+        };
+        (probability(Label::Neighborly) - probability(Label::Hostile)) * SCORE_SCALE
+    }
+}
+
+// which score a line's tolerance change comes from, the "scorer" in assets/scoring.json
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScorerKind {
+    #[default]
+    Afinn,      // only the stacked lexicons, AFINN and the game's own words
+    Classifier, // only the naive bayes model
+    Blend,      // some of each
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScorerConfig {
+    pub kind: ScorerKind,
+    pub model: String,  // relative to the assets folder, only read if the classifier is used
+    pub blend: f64,     // how much of a blended score is the classifier's, 0 to 1
+    pub recency: f64,   // what a sentence counts for next to the one after it, 1 counts every sentence the same
+}
+
+impl Default for ScorerConfig {
+    fn default() -> Self {
+        ScorerConfig {
+            kind: ScorerKind::Afinn,
+            model: String::from("classifier.json"),
+            blend: 0.5,
+            recency: 0.5,
+        }
+    }
+}
+
+enum Method {
+    Afinn,
+    Classifier(NaiveBayes),
+    Blend { model: NaiveBayes, weight: f64 },
+}
+
+impl Method {
+    // the lexicon always runs since topics, intents and manners need its words,
+    // the classifier only changes what the text scores
+    fn score(&self, text: &str, lexicon: &dyn Lexicon) -> SentimentBreakdown {
+        let mut breakdown = score_utterance_with(text, lexicon);
+        match self {
+            Method::Afinn => (),
+            Method::Classifier(model) => {
+                let classified = model.score(text);
+                breakdown.classified = Some(classified);
+                breakdown.score = classified;
+            }
+            Method::Blend { model, weight } => {
+                let classified = model.score(text);
+                breakdown.classified = Some(classified);
+                breakdown.score = weight * classified + (1. - weight) * breakdown.score;
+            }
+        }
+        breakdown
+    }
+}
+
+// scores a player's line the way scoring.json asks, the default is the lexicons on their own
+pub struct Scorer {
+    method: Method,
+    recency: f64,
+}
+
+impl Default for Scorer {
+    fn default() -> Self {
+        Scorer { method: Method::Afinn, recency: ScorerConfig::default().recency }
+    }
+}
+
+impl Scorer {
+    pub fn load(config: &ScorerConfig, asset_dir: &Path) -> Result<Self, LexiconError> {
+        if !(0. ..=1.).contains(&config.recency) {
+            return Err(LexiconError::BadRecency(config.recency));
+        }
+        let model_path = asset_dir.join(&config.model);
+        let method = match config.kind {
+            ScorerKind::Afinn => Method::Afinn,
+            ScorerKind::Classifier => Method::Classifier(NaiveBayes::load(&model_path)?),
+            ScorerKind::Blend => {
+                if !(0. ..=1.).contains(&config.blend) {
+                    return Err(LexiconError::BadBlend(config.blend));
+                }
+                Method::Blend { model: NaiveBayes::load(&model_path)?, weight: config.blend }
+            }
+        };
+        Ok(Scorer { method, recency: config.recency })
+    }
+
+    // a line with several sentences is scored one sentence at a time, see segment::weigh,
+    // the words still come from the whole line
+    pub fn score(&self, text: &str, lexicon: &dyn Lexicon) -> SentimentBreakdown {
+        let mut breakdown = self.method.score(text, lexicon);
+        let weighed = segment::weigh(text, self.recency, |sentence| {
+            let sentence = self.method.score(sentence, lexicon);
+            (!sentence.is_neutral()).then_some(sentence.score)
+        });
+        if let Some((sentences, score)) = weighed {
+            breakdown.sentences = sentences;
+            breakdown.score = score;
+        }
+        breakdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::afinn::Afinn111;
+
+    #[test]
+    fn classifier_learns_from_context() {
+        let corpus = "# a comment\n\
+            neighborly\tno problem, happy to help\n\
+            neighborly\tI'll move my car\n\
+            hostile\tnot my problem\n\
+            hostile\tmove it yourself\n";
+        let examples = parse_corpus(corpus).unwrap();
+        assert_eq!(examples.len(), 4);
+        assert_eq!(parse_corpus("friendly\thi"), Err(1));
+
+        let model = NaiveBayes::train(&examples);
+        assert_eq!(model.classify("that's not my problem"), Label::Hostile);
+        assert_eq!(model.classify("happy to move my car"), Label::Neighborly);
+        assert!(model.score("happy to help") > 0.);
+        assert_eq!(model.score("zebra"), 0.);
+
+        // the model the game ships with still reads
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let shipped = NaiveBayes::load(&asset_dir.join("classifier.json")).unwrap();
+        assert_eq!(shipped.classify("not my problem"), Label::Hostile);
+    }
+
+    #[test]
+    fn last_sentence_counts_most() {
+        let scorer = Scorer::default();
+        let breakdown = scorer.score("You're an idiot. Sorry, I love your garden!", &Afinn111);
+        let sentences: Vec<(&str, f64)> = breakdown.sentences.iter().map(|s| (s.text.as_str(), s.weight)).collect();
+        assert_eq!(sentences, vec![("You're an idiot.", 0.5), ("Sorry, I love your garden!", 1.)]);
+        assert!(breakdown.score > 0.);
+        assert!(scorer.score("Sorry, I love your garden! You're an idiot.", &Afinn111).score < breakdown.score);
+        assert_eq!(scorer.score("I love your garden", &Afinn111).sentences, Vec::new());
+    }
+
+    #[test]
+    fn neutral_sentences_count_for_nothing() {
+        let scorer = Scorer::default();
+        let alone = scorer.score("I love your garden!", &Afinn111);
+        let breakdown = scorer.score("Ok. The car is outside. I love your garden!", &Afinn111);
+        assert_eq!(breakdown.sentences.iter().map(|s| s.weight).collect::<Vec<_>>(), vec![0., 0., 1.]);
+        assert_eq!(breakdown.score, alone.score);
+        // nothing to weigh, so the whole line scores like one sentence
+        assert_eq!(scorer.score("Ok. The car is outside.", &Afinn111).score, scorer.method.score("Ok. The car is outside.", &Afinn111).score);
+    }
+}
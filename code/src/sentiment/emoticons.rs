@@ -0,0 +1,43 @@
+use super::lexicon::Lexicon;
+
+// scored like AFINN, -5 to 5, the tokenizer matches these as typed so ":D" and ":d" are listed separately
+pub const EMOTICONS: [(&str, i8); 30] = [
+    (":)", 2), (":-)", 2), (":]", 2), ("=)", 2), ("(:", 2), (":o)", 2),
+    (":D", 3), (":-D", 3), (";)", 2), (";-)", 2), (":P", 1), (":-P", 1), (":p", 1), (":-p", 1),
+    ("<3", 3), ("<33", 3), (":*", 2), (":-*", 2), ("^^", 2), ("^_^", 2),
+    ("</3", -3), (":(", -2), (":-(", -2), (":[", -2), ("=(", -2), ("):", -2),
+    (":'(", -2), (":'-(", -2), (">:(", -3), (">:-(", -3),
+];
+
+// looked up without skin tones or the emoji style selector, so every version of an emoji scores the same
+pub const EMOJI: [(&str, i8); 44] = [
+    ("\u{1f600}", 2), ("\u{1f603}", 2), ("\u{1f604}", 3), ("\u{1f601}", 3), ("\u{1f60a}", 3),
+    ("\u{1f642}", 2), ("\u{1f60d}", 4), ("\u{1f970}", 4), ("\u{1f618}", 3), ("\u{1f602}", 2),
+    ("\u{1f923}", 2), ("\u{1f609}", 2), ("\u{1f607}", 2), ("\u{1f917}", 3), ("\u{1f44d}", 2),
+    ("\u{1f44f}", 2), ("\u{1f64f}", 2), ("\u{1f44b}", 1), ("\u{2764}", 3), ("\u{1f495}", 3),
+    ("\u{1f496}", 3), ("\u{1f389}", 3), ("\u{1f36a}", 1), ("\u{1f490}", 2), ("\u{2600}", 1),
+    ("\u{1f44e}", -2), ("\u{1f494}", -3), ("\u{1f622}", -2), ("\u{1f62d}", -2), ("\u{1f61e}", -2),
+    ("\u{1f641}", -2), ("\u{2639}", -2), ("\u{1f620}", -3), ("\u{1f621}", -4), ("\u{1f92c}", -5),
+    ("\u{1f595}", -5), ("\u{1f4a9}", -3), ("\u{1f644}", -2), ("\u{1f612}", -2), ("\u{1f624}", -2),
+    ("\u{1f92e}", -3), ("\u{1f631}", -2), ("\u{1f628}", -2), ("\u{1f52a}", -3),
+];
+
+// the emoticon `text` starts with, the longest one if several fit
+pub fn emoticon_at(text: &str) -> Option<&'static str> {
+    EMOTICONS.iter()
+        .map(|(emoticon, _)| *emoticon)
+        .filter(|emoticon| text.starts_with(emoticon))
+        .max_by_key(|emoticon| emoticon.len())
+}
+
+// the table that is compiled into the game, it sits in the lexicon stack next to AFINN
+pub struct Emoticons;
+
+impl Lexicon for Emoticons {
+    fn score(&self, word: &str) -> Option<i8> {
+        EMOTICONS.iter()
+            .chain(EMOJI.iter())
+            .find(|(key, _)| *key == word)
+            .map(|(_, value)| *value)
+    }
+}
@@ -0,0 +1,247 @@
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::Deserialize;
+
+use super::lexicon::{phrase_length, Lexicon};
+use super::{is_modifier, Token};
+
+// real words that are one dropped letter away from a lexicon word, "than" is not a typo of "thank"
+const REAL_WORDS: [&str; 14] = ["back", "cars", "down", "fare", "gave", "lose", "lots", "morning", "same",
+    "sing", "than", "wear", "wore", "were"];
+// shorter words only count as a typo if a letter was dropped or two letters were swapped,
+// anything else turns too many real words into lexicon words ("have" into "hate")
+const LONG_WORD: usize = 7;
+
+// how forgiving the scorer is about typos, the "typos" entry in assets/scoring.json
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FuzzyConfig {
+    pub max_distance: usize,    // most edits a typo can be away from the word it's matched to
+    pub min_length: usize,      // shorter words are never corrected
+    pub penalty: f64,           // how much of a word's score every edit costs, 0 doesn't punish typos at all
+}
+
+impl Default for FuzzyConfig {
+    fn default() -> Self {
+        FuzzyConfig {
+            max_distance: 1,
+            min_length: 4,
+            penalty: 0.25,
+        }
+    }
+}
+
+// a word that wasn't in the lexicon and the lexicon word it was probably meant to be
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correction {
+    pub word: String,
+    pub distance: usize,
+    pub confidence: f64,    // what the word's score is multiplied by
+}
+
+// every single word of a lexicon in a BK-tree, so the ones a typo or two away are found without checking them all
+pub struct FuzzyIndex {
+    config: FuzzyConfig,
+    nodes: Vec<BkNode>,
+}
+
+struct BkNode {
+    word: String,
+    children: Vec<(usize, usize)>,  // levenshtein distance to the child, index of the child
+}
+
+impl FuzzyIndex {
+    pub fn new(config: FuzzyConfig, lexicon: &dyn Lexicon) -> Self {
+        let mut index = FuzzyIndex { config, nodes: Vec::new() };
+        let mut words = lexicon.keys();
+        // sorted so the tree, and which of two equally close words wins, is the same every run
+        words.sort();
+        words.dedup();
+        for word in words {
+            if phrase_length(&word) == 1 && word.chars().all(char::is_alphabetic) {
+                index.insert(word);
+            }
+        }
+        index
+    }
+
+    pub fn config(&self) -> &FuzzyConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn insert(&mut self, word: String) {
+        if self.nodes.is_empty() {
+            self.nodes.push(BkNode { word, children: Vec::new() });
+            return;
+        }
+        let mut node = 0;
+        loop {
+            let distance = levenshtein(&self.nodes[node].word, &word);
+            if distance == 0 {
+                return;
+            }
+            match self.nodes[node].children.iter().find(|(d, _)| *d == distance) {
+                Some(&(_, child)) => node = child,
+                None => {
+                    self.nodes.push(BkNode { word, children: Vec::new() });
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.push((distance, child));
+                    return;
+                }
+            }
+        }
+    }
+
+    // every lexicon word the token could be a typo of, closest first
+    pub fn candidates(&self, token: &Token) -> Vec<(String, usize)> {
+        let stemmer = Stemmer::create(Algorithm::English);
+        if token.word.chars().count() < self.config.min_length
+            || !token.word.chars().all(char::is_alphabetic)
+            || REAL_WORDS.contains(&token.word.as_str())
+            || is_modifier(&stemmer, token)
+            || self.nodes.is_empty()
+        {
+            return Vec::new();
+        }
+
+        let mut found = Vec::new();
+        for typed in [&token.word, &token.stem] {
+            // a swap is one typo but two levenshtein edits, so search twice as far and then count properly
+            let mut stack = vec![0];
+            while let Some(node) = stack.pop() {
+                let node = &self.nodes[node];
+                let distance = levenshtein(&node.word, typed);
+                let radius = self.config.max_distance * 2;
+                if distance <= radius && plausible_typo(typed, &node.word, self.config.max_distance) {
+                    found.push((node.word.clone(), typo_distance(typed, &node.word)));
+                }
+                for &(d, child) in &node.children {
+                    if d + radius >= distance && d <= distance + radius {
+                        stack.push(child);
+                    }
+                }
+            }
+        }
+        found.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        found.dedup_by(|a, b| a.0 == b.0);
+        found
+    }
+}
+
+// picks the closest candidate, unless the closest ones disagree about whether the word was nice or mean
+pub fn pick_correction(lexicon: &dyn Lexicon, candidates: &[(String, usize)], config: &FuzzyConfig) -> Option<Correction> {
+    let best = candidates.iter().map(|(_, distance)| *distance).min()?;
+    let closest: Vec<(&String, i8)> = candidates.iter()
+        .filter(|(_, distance)| *distance == best)
+        .filter_map(|(word, _)| lexicon.score(word).map(|value| (word, value)))
+        .collect();
+    let (word, value) = *closest.first()?;
+    if closest.iter().any(|(_, other)| other.signum() != value.signum()) {
+        return None;
+    }
+    Some(Correction {
+        word: word.clone(),
+        distance: best,
+        confidence: (1. - config.penalty * best as f64).max(0.),
+    })
+}
+
+fn plausible_typo(typed: &str, word: &str, max_distance: usize) -> bool {
+    let distance = typo_distance(typed, word);
+    if distance == 0 || distance > max_distance || typed.chars().next() != word.chars().next() {
+        return false;
+    }
+    if typed.chars().count() >= LONG_WORD {
+        return true;
+    }
+    let dropped = word.chars().count().saturating_sub(typed.chars().count());
+    dropped == distance || (dropped == 0 && levenshtein(typed, word) == 2 * distance && is_swap(typed, word))
+}
+
+// true if `word` is `typed` with some neighboring letters swapped back
+fn is_swap(typed: &str, word: &str) -> bool {
+    let typed: Vec<char> = typed.chars().collect();
+    let word: Vec<char> = word.chars().collect();
+    let mut i = 0;
+    while i < typed.len() {
+        if typed[i] == word[i] {
+            i += 1;
+        } else if i + 1 < typed.len() && typed[i] == word[i + 1] && typed[i + 1] == word[i] {
+            i += 2;
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// levenshtein, but two swapped letters only count as one typo
+pub fn typo_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            table[i][j] = (table[i - 1][j] + 1).min(table[i][j - 1] + 1).min(table[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                table[i][j] = table[i][j].min(table[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    table[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::afinn::Afinn111;
+    use crate::sentiment::tokenize;
+
+    fn correct(index: &FuzzyIndex, word: &str) -> Option<String> {
+        let token = &tokenize(word)[0];
+        pick_correction(&Afinn111, &index.candidates(token), index.config()).map(|fix| fix.word)
+    }
+
+    #[test]
+    fn typos_find_their_word_and_real_words_are_left_alone() {
+        assert_eq!(typo_distance("thnak", "thank"), 1);
+        assert_eq!(levenshtein("thnak", "thank"), 2);
+
+        let index = FuzzyIndex::new(FuzzyConfig::default(), &Afinn111);
+        assert_eq!(correct(&index, "thnak").as_deref(), Some("thank"));
+        assert_eq!(correct(&index, "sory").as_deref(), Some("sorry"));
+        assert_eq!(correct(&index, "awsome").as_deref(), Some("awesome"));
+        assert_eq!(correct(&index, "wonderfl").as_deref(), Some("wonderful"));
+        for word in ["have", "life", "made", "said", "than", "read", "right", "bad", "flibbertigibbet"] {
+            assert_eq!(correct(&index, word), None, "{}", word);
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::Deserialize;
+
+use super::afinn::Afinn111;
+use super::classifier::{Scorer, ScorerConfig};
+use super::emoticons::Emoticons;
+use super::fuzzy::{pick_correction, Correction, FuzzyConfig, FuzzyIndex};
+use super::{simplify_word, Token};
+use crate::roster::NeighborRoster;
+
+// anything that can put a number on a (simplified) word or a phrase of words as typed
+pub trait Lexicon {
+    fn score(&self, word: &str) -> Option<i8>;
+
+    // every word and phrase it knows, only used to build the typo index
+    fn keys(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    // the word `token` was probably meant to be, if it looks like a typo of one
+    fn correct(&self, _token: &Token) -> Option<Correction> {
+        None
+    }
+
+    // how many words are in the longest phrase this lexicon knows
+    fn longest_phrase(&self) -> usize {
+        1
+    }
+}
+
+pub fn phrase_length(key: &str) -> usize {
+    key.split_whitespace().count()
+}
+
+// a word list read from a file, either a json object of word -> score
+// or AFINN's own tab separated "word<tab>score" lines (.txt)
+#[derive(Debug, Clone, Default)]
+pub struct WordList {
+    words: HashMap<String, i8>,
+    longest_phrase: usize,
+}
+
+impl WordList {
+    pub fn load(path: &Path) -> Result<Self, LexiconError> {
+        let text = fs::read_to_string(path).map_err(|source| LexiconError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let words = if path.extension().and_then(|ext| ext.to_str()) == Some("txt") {
+            parse_tsv(&text).map_err(|line| LexiconError::BadLine { path: path.to_path_buf(), line })?
+        } else {
+            serde_json::from_str(&text).map_err(|source| LexiconError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?
+        };
+        Ok(WordList::new(words))
+    }
+
+    // single words go through the same stemming as the player's lines, so files can use normal words,
+    // phrases are matched as typed
+    pub fn new(words: HashMap<String, i8>) -> Self {
+        let stemmer = Stemmer::create(Algorithm::English);
+        let mut simplified = HashMap::new();
+        let mut longest_phrase = 1;
+        for (word, value) in words {
+            let word = word.to_lowercase();
+            let key = match phrase_length(&word) {
+                0 => continue,
+                1 => simplify_word(&stemmer, word.trim()),
+                len => {
+                    longest_phrase = longest_phrase.max(len);
+                    word.split_whitespace().collect::<Vec<_>>().join(" ")
+                }
+            };
+            simplified.entry(key).or_insert(value);
+        }
+        WordList { words: simplified, longest_phrase }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl Lexicon for WordList {
+    fn score(&self, word: &str) -> Option<i8> {
+        self.words.get(word).copied()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.words.keys().cloned().collect()
+    }
+
+    fn longest_phrase(&self) -> usize {
+        self.longest_phrase
+    }
+}
+
+// returns the number of the first line that isn't "word<tab>score"
+fn parse_tsv(text: &str) -> Result<HashMap<String, i8>, usize> {
+    let mut words = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (word, value) = line.rsplit_once('\t').ok_or(index + 1)?;
+        let value = value.trim().parse().map_err(|_| index + 1)?;
+        words.insert(word.trim().to_string(), value);
+    }
+    Ok(words)
+}
+
+// several lexicons on top of each other, when they disagree the last one pushed wins
+#[derive(Default)]
+pub struct StackedLexicon {
+    layers: Vec<Box<dyn Lexicon + Send + Sync>>,
+}
+
+impl StackedLexicon {
+    pub fn push(&mut self, layer: impl Lexicon + Send + Sync + 'static) {
+        self.layers.push(Box::new(layer));
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl Lexicon for StackedLexicon {
+    fn score(&self, word: &str) -> Option<i8> {
+        self.layers.iter().rev().find_map(|layer| layer.score(word))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.layers.iter().flat_map(|layer| layer.keys()).collect()
+    }
+
+    fn longest_phrase(&self) -> usize {
+        self.layers.iter().map(|layer| layer.longest_phrase()).max().unwrap_or(1)
+    }
+}
+
+// one neighbor's own words checked before everyone else's
+pub struct WithOverrides<'a> {
+    overrides: Option<&'a WordList>,
+    base: &'a dyn Lexicon,
+    typos: &'a [FuzzyIndex],    // the base index and the neighbor's own, empty if typos aren't corrected
+}
+
+impl Lexicon for WithOverrides<'_> {
+    fn score(&self, word: &str) -> Option<i8> {
+        self.overrides.and_then(|overrides| overrides.score(word)).or_else(|| self.base.score(word))
+    }
+
+    fn correct(&self, token: &Token) -> Option<Correction> {
+        // a word that is spelled right is never a typo, however close it is to another one
+        if self.score(&token.word).is_some() || self.score(&token.stem).is_some() {
+            return None;
+        }
+        let config = self.typos.first()?.config();
+        let candidates: Vec<(String, usize)> = self.typos.iter().flat_map(|index| index.candidates(token)).collect();
+        pick_correction(self, &candidates, config)
+    }
+
+    fn longest_phrase(&self) -> usize {
+        let overrides = self.overrides.map_or(1, |overrides| overrides.longest_phrase());
+        overrides.max(self.base.longest_phrase())
+    }
+}
+
+// one entry in the "lexicons" list of assets/scoring.json
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LexiconSource {
+    Builtin(String),
+    File {
+        path: String,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LexiconConfig {
+    pub lexicons: Vec<LexiconSource>,   // lowest priority first
+    #[serde(default)]
+    pub typos: Option<FuzzyConfig>,     // leave it out to only score words spelled right
+    #[serde(default)]
+    pub scorer: ScorerConfig,           // the lexicons, the trained classifier or a blend of both
+}
+
+#[derive(Debug)]
+pub enum LexiconError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, source: serde_json::Error },
+    BadLine { path: PathBuf, line: usize },
+    UnknownBuiltin(String),
+    Empty,
+    BadTypoPenalty(f64),
+    BadModel { path: PathBuf, source: serde_json::Error },
+    BadBlend(f64),
+    BadRecency(f64),
+    Neighbor { id: String, source: Box<LexiconError> },
+}
+
+impl fmt::Display for LexiconError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LexiconError::Io { path, source } =>
+                write!(f, "could not read {}: {}", path.display(), source),
+            LexiconError::Parse { path, source } =>
+                write!(f, "{} is not a valid lexicon: {}", path.display(), source),
+            LexiconError::BadLine { path, line } =>
+                write!(f, "line {} of {} is not \"word<tab>score\"", line, path.display()),
+            LexiconError::UnknownBuiltin(name) =>
+                write!(f, "there is no built in lexicon called \"{}\"", name),
+            LexiconError::Empty =>
+                write!(f, "no lexicons are configured"),
+            LexiconError::BadTypoPenalty(penalty) =>
+                write!(f, "the typo penalty has to be between 0 and 1, not {}", penalty),
+            LexiconError::BadModel { path, source } =>
+                write!(f, "{} is not a valid classifier model, retrain it with `cargo run --bin classifier -- train`: {}",
+                    path.display(), source),
+            LexiconError::BadBlend(blend) =>
+                write!(f, "the scorer blend has to be between 0 and 1, not {}", blend),
+            LexiconError::BadRecency(recency) =>
+                write!(f, "the scorer recency has to be between 0 and 1, not {}", recency),
+            LexiconError::Neighbor { id, source } =>
+                write!(f, "neighbor \"{}\" has a bad lexicon: {}", id, source),
+        }
+    }
+}
+
+impl std::error::Error for LexiconError {}
+
+// every lexicon the game scores with, the configured stack plus each neighbor's overrides
+pub struct LexiconSet {
+    base: StackedLexicon,
+    base_typos: Vec<FuzzyIndex>,
+    neighbors: HashMap<String, NeighborLexicon>,
+    scorer: Scorer,
+}
+
+struct NeighborLexicon {
+    words: WordList,
+    typos: Vec<FuzzyIndex>,     // the base index followed by one for these words, if typos are corrected
+}
+
+impl LexiconSet {
+    // reads the config at `path`, lexicon files are relative to `asset_dir`
+    pub fn load(path: &Path, asset_dir: &Path, roster: &NeighborRoster) -> Result<Self, LexiconError> {
+        let json = fs::read_to_string(path).map_err(|source| LexiconError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config: LexiconConfig = serde_json::from_str(&json).map_err(|source| LexiconError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut set = Self::from_config(&config, asset_dir)?;
+        for neighbor in roster.iter() {
+            if let Some(file) = &neighbor.lexicon {
+                let words = WordList::load(&asset_dir.join(file)).map_err(|source| LexiconError::Neighbor {
+                    id: neighbor.id.clone(),
+                    source: Box::new(source),
+                })?;
+                set.add_neighbor(&neighbor.id, words, config.typos.as_ref());
+            }
+        }
+        Ok(set)
+    }
+
+    pub fn from_config(config: &LexiconConfig, asset_dir: &Path) -> Result<Self, LexiconError> {
+        let mut base = StackedLexicon::default();
+        for source in &config.lexicons {
+            match source {
+                LexiconSource::Builtin(name) if name == "afinn-111" => base.push(Afinn111),
+                LexiconSource::Builtin(name) if name == "emoticons" => base.push(Emoticons),
+                LexiconSource::Builtin(name) => return Err(LexiconError::UnknownBuiltin(name.clone())),
+                LexiconSource::File { path } => base.push(WordList::load(&asset_dir.join(path))?),
+            }
+        }
+        if base.is_empty() {
+            return Err(LexiconError::Empty);
+        }
+        let mut base_typos = Vec::new();
+        if let Some(typos) = &config.typos {
+            if !(0. ..=1.).contains(&typos.penalty) {
+                return Err(LexiconError::BadTypoPenalty(typos.penalty));
+            }
+            base_typos.push(FuzzyIndex::new(typos.clone(), &base));
+        }
+        let scorer = Scorer::load(&config.scorer, asset_dir)?;
+        Ok(LexiconSet { base, base_typos, neighbors: HashMap::new(), scorer })
+    }
+
+    fn add_neighbor(&mut self, id: &str, words: WordList, typos: Option<&FuzzyConfig>) {
+        let mut indexes = Vec::new();
+        if let Some(typos) = typos {
+            indexes.push(FuzzyIndex::new(typos.clone(), &self.base));
+            indexes.push(FuzzyIndex::new(typos.clone(), &words));
+        }
+        self.neighbors.insert(id.to_string(), NeighborLexicon { words, typos: indexes });
+    }
+
+    // how many lexicons are stacked under the per-neighbor overrides, one for every entry in "lexicons"
+    pub fn len(&self) -> usize {
+        self.base.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.base.is_empty()
+    }
+
+    pub fn scorer(&self) -> &Scorer {
+        &self.scorer
+    }
+
+    // the lexicon to score lines said to `neighbor` with
+    pub fn for_neighbor(&self, neighbor: &str) -> WithOverrides<'_> {
+        let own = self.neighbors.get(neighbor);
+        WithOverrides {
+            overrides: own.map(|own| &own.words),
+            base: &self.base,
+            typos: own.map_or(&self.base_typos, |own| &own.typos),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_lexicons_and_neighbor_overrides_win() {
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let roster = NeighborRoster::load(&asset_dir.join("neighbors.json"), &asset_dir).unwrap();
+        let set = LexiconSet::load(&asset_dir.join("scoring.json"), &asset_dir, &roster).unwrap();
+
+        // "sugar" is only in the game lexicon, "cowboy" is only a problem for billy
+        assert_eq!(set.for_neighbor("cathy").score("sugar"), Some(1));
+        assert_eq!(set.for_neighbor("cathy").score("cowboy"), None);
+        assert_eq!(set.for_neighbor("billy").score("cowboy"), Some(-2));
+        assert_eq!(set.for_neighbor("billy").score("good"), Some(3));
+        assert_eq!(set.for_neighbor("billy").longest_phrase(), 4);
+        assert_eq!(set.for_neighbor("karen").score("\u{1f595}"), Some(-5));
+
+        let mut stack = StackedLexicon::default();
+        stack.push(Afinn111);
+        stack.push(WordList::new(HashMap::from([(String::from("good"), 1)])));
+        assert_eq!(stack.score("good"), Some(1));
+        assert_eq!(stack.score("bad"), Some(-3));
+    }
+
+    #[test]
+    fn typos_are_corrected_with_the_configured_penalty() {
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let config: LexiconConfig = serde_json::from_str(r#"{
+            "lexicons": [{ "builtin": "afinn-111" }],
+            "typos": { "penalty": 0.5 }
+        }"#).unwrap();
+        let set = LexiconSet::from_config(&config, &asset_dir).unwrap();
+        let breakdown = crate::sentiment::score_utterance_with("thnak you", &set.for_neighbor("cathy"));
+        assert_eq!(breakdown.corrected, vec![(String::from("thnak"), String::from("thank"))]);
+        assert_eq!(breakdown.score, 1.);
+
+        let config = LexiconConfig { typos: Some(FuzzyConfig { penalty: 2., ..FuzzyConfig::default() }), ..config };
+        assert!(matches!(LexiconSet::from_config(&config, &asset_dir), Err(LexiconError::BadTypoPenalty(_))));
+    }
+
+    #[test]
+    fn spelling_it_right_never_scores_below_a_typo() {
+        let asset_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let json = fs::read_to_string(asset_dir.join("scoring.json")).unwrap();
+        let set = LexiconSet::from_config(&serde_json::from_str(&json).unwrap(), &asset_dir).unwrap();
+        let lexicon = set.for_neighbor("cathy");
+        for (right, typo) in [("wonderful", "wonderfl"), ("amazing", "amazng"), ("awesome", "awsome"), ("thank you", "thnak you")] {
+            let right_score = crate::sentiment::score_utterance_with(right, &lexicon);
+            assert!(right_score.corrected.is_empty(), "{} was corrected", right);
+            assert!(right_score.score >= crate::sentiment::score_utterance_with(typo, &lexicon).score, "{} scored below {}", right, typo);
+        }
+    }
+
+    #[test]
+    fn afinn_text_files_load() {
+        assert_eq!(parse_tsv("abandon\t-2\ncan't stand\t-3\n"), Ok(HashMap::from([
+            (String::from("abandon"), -2),
+            (String::from("can't stand"), -3),
+        ])));
+        assert_eq!(parse_tsv("abandon -2"), Err(1));
+    }
+}
@@ -0,0 +1,131 @@
+use rust_stemmers::{Algorithm, Stemmer};
+
+use super::lexicon::Lexicon;
+use super::{simplify_word, Token};
+
+// chat shorthand and what it is short for, looked up after stretched letters are squashed
+const CHAT_WORDS: [(&str, &str); 44] = [
+    ("thx", "thanks"), ("thnx", "thanks"), ("tnx", "thanks"), ("ty", "thank you"), ("tysm", "thank you so much"),
+    ("u", "you"), ("ya", "you"), ("ur", "your"), ("r", "are"), ("y", "why"), ("n", "and"),
+    ("gr8", "great"), ("h8", "hate"), ("l8r", "later"), ("m8", "mate"), ("w8", "wait"), ("b4", "before"),
+    ("2day", "today"), ("2nite", "tonight"), ("4ever", "forever"),
+    ("pls", "please"), ("plz", "please"), ("plox", "please"), ("luv", "love"), ("gud", "good"),
+    ("ppl", "people"), ("sry", "sorry"), ("srry", "sorry"), ("soz", "sorry"), ("np", "no problem"),
+    ("nvm", "never mind"), ("idk", "i don't know"), ("ily", "i love you"), ("srsly", "seriously"),
+    ("cuz", "because"), ("coz", "because"), ("bc", "because"), ("im", "i'm"), ("dunno", "don't know"),
+    ("gonna", "going to"), ("wanna", "want to"), ("gimme", "give me"), ("k", "okay"), ("kk", "okay"),
+];
+// what digits in the middle of a word usually stand in for
+const LEET: [(char, char); 6] = [('0', 'o'), ('1', 'i'), ('3', 'e'), ('4', 'a'), ('5', 's'), ('7', 't')];
+// a letter typed this many times in a row is stretched out, no english word has three
+const ELONGATED_RUN: usize = 3;
+
+// a lowercased word with every stretched out letter squashed down to one, and if anything was squashed
+pub fn squash_elongation(word: &str) -> (String, bool) {
+    let mut squashed = String::new();
+    let mut elongated = false;
+    for run in runs(word) {
+        if run.1 >= ELONGATED_RUN && run.0.is_alphabetic() {
+            elongated = true;
+            squashed.push(run.0);
+        } else {
+            squashed.extend(std::iter::repeat_n(run.0, run.1));
+        }
+    }
+    (squashed, elongated)
+}
+
+// swaps the digits in words like "c00l" and "n1ce" for the letters they look like,
+// words that are all digits and ordinals like "2nd" are left alone
+pub fn unleet(word: &str) -> String {
+    let is_ordinal = ["st", "nd", "rd", "th"].iter().any(|end| {
+        word.strip_suffix(end).is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+    });
+    if is_ordinal || !word.chars().any(char::is_alphabetic) || !word.chars().any(|c| c.is_ascii_digit()) {
+        return word.to_string();
+    }
+    word.chars()
+        .map(|c| LEET.iter().find(|(digit, _)| *digit == c).map_or(c, |(_, letter)| *letter))
+        .collect()
+}
+
+// the words a bit of chat shorthand stands for
+pub fn expand(word: &str) -> Option<Vec<&'static str>> {
+    CHAT_WORDS.iter()
+        .find(|(short, _)| *short == word)
+        .map(|(_, long)| long.split(' ').collect())
+}
+
+// a stretched out word was squashed to single letters by the tokenizer, which is wrong for "goooood",
+// so every run is tried with two letters as well and the spelling the lexicon knows with the most double letters is kept
+pub fn restore_doubles(tokens: &mut [Token], lexicon: &dyn Lexicon) {
+    let stemmer = Stemmer::create(Algorithm::English);
+    for token in tokens.iter_mut().filter(|token| token.elongated) {
+        if !token.typed.chars().all(char::is_alphabetic) {
+            continue;
+        }
+        let runs = runs(&token.typed);
+        let stretched: Vec<usize> = (0..runs.len()).filter(|i| runs[*i].1 >= ELONGATED_RUN).collect();
+        // more than a few stretched runs in one word is just noise
+        if stretched.len() > 3 {
+            continue;
+        }
+        // "god" is in the lexicon too, but stretching out "good" is far more likely
+        for doubles in (0..1usize << stretched.len()).rev() {
+            let spelling: String = runs.iter()
+                .enumerate()
+                .map(|(i, (letter, len))| {
+                    let len = match stretched.iter().position(|run| *run == i) {
+                        Some(bit) if doubles & (1 << bit) != 0 => 2,
+                        Some(_) => 1,
+                        None => *len,
+                    };
+                    std::iter::repeat_n(*letter, len).collect::<String>()
+                })
+                .collect();
+            let stem = simplify_word(&stemmer, &spelling);
+            if lexicon.score(&stem).is_some() {
+                token.word = spelling;
+                token.stem = stem;
+                break;
+            }
+        }
+    }
+}
+
+// every letter of `word` with how many times it was typed in a row
+fn runs(word: &str) -> Vec<(char, usize)> {
+    let mut runs: Vec<(char, usize)> = Vec::new();
+    for c in word.chars() {
+        match runs.last_mut() {
+            Some((letter, len)) if *letter == c => *len += 1,
+            _ => runs.push((c, 1)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::afinn::Afinn111;
+    use crate::sentiment::tokenize;
+
+    fn heard(text: &str) -> Vec<String> {
+        let mut tokens = tokenize(text);
+        restore_doubles(&mut tokens, &Afinn111);
+        tokens.into_iter().map(|token| token.word).collect()
+    }
+
+    #[test]
+    fn chat_is_normalized() {
+        assert_eq!(heard("soooo niiice"), vec!["so", "nice"]);
+        assert_eq!(heard("goooood job, sorrrry"), vec!["good", "job", "sorry"]);
+        assert_eq!(heard("thx, u r gr8"), vec!["thanks", "you", "are", "great"]);
+        assert_eq!(heard("i h8 ur c00l car"), vec!["i", "hate", "your", "cool", "car"]);
+        assert_eq!(heard("on the 2nd of may"), vec!["on", "2nd", "of", "may"]);
+        assert_eq!(heard("see u b4 l8r, friends 4ever"), vec!["see", "you", "before", "later", "friends", "forever"]);
+        assert!(tokenize("soooo good")[0].elongated);
+        assert!(!tokenize("good")[0].elongated);
+    }
+}
@@ -0,0 +1,99 @@
+// a period after one of these is part of the word, not the end of a sentence
+const ABBREVIATIONS: [&str; 11] = ["mr", "mrs", "ms", "dr", "st", "jr", "sr", "vs", "etc", "e.g", "i.e"];
+const SENTENCE_ENDS: [char; 3] = ['.', '!', '?'];
+// quotes and brackets right after the end of a sentence still belong to it
+const CLOSERS: [char; 5] = ['"', '\'', ')', ']', '”'];
+
+// one sentence of a player's line, with what it scored and how much of that counted
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceScore {
+    pub text: String,
+    pub score: f64,
+    pub weight: f64,    // the last sentence is worth 1, the ones before it less the further back they are, 0 if it was neutral
+}
+
+// splits a line into sentences at ., ! and ? followed by a space or the end of the line,
+// keeping titles like "Mrs." and numbers like "2.5" in one piece,
+// bits with no words in them like a trailing ":(" go with the sentence before them
+pub fn split(text: &str) -> Vec<&str> {
+    let mut sentences: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if !SENTENCE_ENDS.contains(&c) {
+            continue;
+        }
+        let mut end = index + c.len_utf8();
+        // "?!", "..." and a closing quote all end the same sentence
+        while let Some((next, c)) = chars.next_if(|(_, c)| SENTENCE_ENDS.contains(c) || CLOSERS.contains(c)) {
+            end = next + c.len_utf8();
+        }
+        if !chars.peek().is_none_or(|(_, c)| c.is_whitespace()) {
+            continue;
+        }
+        let word_before = text[start..index].split_whitespace().last().unwrap_or("").to_lowercase();
+        let is_abbreviation = c == '.' && end == index + 1
+            && (ABBREVIATIONS.contains(&word_before.as_str())
+                || (word_before.chars().count() == 1 && word_before.chars().all(char::is_alphabetic)));
+        if is_abbreviation {
+            continue;
+        }
+        push_sentence(&mut sentences, text, start, end);
+        start = end;
+    }
+    push_sentence(&mut sentences, text, start, text.len());
+    sentences.into_iter()
+        .map(|(start, end)| text[start..end].trim())
+        .filter(|sentence| !sentence.is_empty())
+        .collect()
+}
+
+// scores a line one sentence at a time and puts it back together like people hear it, the last sentence
+// counts in full and each one before it `recency` times what the one after it does,
+// sentences `score` calls neutral (None) count for nothing and the result is divided by the weights,
+// so "ok. fine. I love it!" is worth as much as "I love it!" on its own,
+// None when there's only one sentence or none of them scored
+pub fn weigh(text: &str, recency: f64, mut score: impl FnMut(&str) -> Option<f64>) -> Option<(Vec<SentenceScore>, f64)> {
+    let sentences = split(text);
+    if sentences.len() < 2 {
+        return None;
+    }
+    let last = sentences.len() - 1;
+    let scored: Vec<SentenceScore> = sentences.iter()
+        .enumerate()
+        .map(|(index, sentence)| {
+            let (score, weight) = score(sentence).map_or((0., 0.), |score| (score, recency.powi((last - index) as i32)));
+            SentenceScore { text: sentence.to_string(), score, weight }
+        })
+        .collect();
+    let weights: f64 = scored.iter().map(|sentence| sentence.weight).sum();
+    if weights == 0. {
+        return None;
+    }
+    let total = scored.iter().map(|sentence| sentence.score * sentence.weight).sum::<f64>() / weights;
+    Some((scored, total))
+}
+
+fn push_sentence(sentences: &mut Vec<(usize, usize)>, text: &str, start: usize, end: usize) {
+    match sentences.last_mut() {
+        Some(last) if !text[start..end].chars().any(char::is_alphanumeric) => last.1 = end,
+        _ => sentences.push((start, end)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_split_into_sentences() {
+        assert_eq!(split("Sorry about the car. I'm moving it now. Your party sounds fun!"),
+            vec!["Sorry about the car.", "I'm moving it now.", "Your party sounds fun!"]);
+        assert_eq!(split("Excuse me, Mrs. Martinez. Can I help?!"), vec!["Excuse me, Mrs. Martinez.", "Can I help?!"]);
+        assert_eq!(split("it costs 2.5 dollars... \"fine.\" ok"), vec!["it costs 2.5 dollars...", "\"fine.\"", "ok"]);
+        assert_eq!(split("hi :) bye"), vec!["hi :) bye"]);
+        assert_eq!(split("no way!!! :("), vec!["no way!!! :("]);
+        assert_eq!(split("ok. :( fine"), vec!["ok.", ":( fine"]);
+        assert_eq!(split(""), Vec::<&str>::new());
+    }
+}
@@ -0,0 +1,166 @@
+use rust_stemmers::{Algorithm, Stemmer};
+
+use super::emoticons::emoticon_at;
+use super::normalize::{expand, squash_elongation, unleet};
+use super::simplify_word;
+
+const ARTICLES: [&str; 3] = ["a", "an", "the"];
+const CLAUSE_PUNCTUATION: [char; 6] = [',', ';', ':', '.', '!', '?'];
+// phone keyboards like to type these instead of a plain '
+const APOSTROPHES: [char; 3] = ['\u{2019}', '\u{2018}', '\u{02bc}'];
+
+// one word (or emoji, or emoticon) of the player's line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub typed: String,  // lowercased but otherwise as typed
+    pub word: String,   // with stretched letters, leetspeak and chat shorthand undone, phrases are matched on these
+    pub stem: String,   // what single words are looked up as
+    pub ends_clause: bool,  // followed by a comma, full stop or the like
+    pub shouted: bool,      // typed in capitals
+    pub elongated: bool,    // typed with a stretched out letter, like "soooo"
+}
+
+// splits a line of dialogue into lowercased words, emoji and emoticons, drops the articles and punctuation,
+// spells out chat shorthand and stems every word, "kind of" and "sort of" are kept together as one word
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let stemmer = Stemmer::create(Algorithm::English);
+    let text: String = text.chars().map(|c| if APOSTROPHES.contains(&c) { '\'' } else { c }).collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if c.is_alphanumeric() {
+            let mut typed = String::from(c);
+            // apostrophes and hyphens only count inside a word, so "don't" and "well-being" stay whole
+            while let Some(&(_, next)) = chars.peek() {
+                if next.is_alphanumeric() {
+                    typed.push(next);
+                    chars.next();
+                } else if next == '\'' || next == '-' {
+                    let mut ahead = chars.clone();
+                    ahead.next();
+                    if !matches!(ahead.peek(), Some((_, c)) if c.is_alphanumeric()) {
+                        break;
+                    }
+                    typed.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            push_word(&mut tokens, &stemmer, &typed);
+        } else if let Some(emoticon) = emoticon_at(&text[index..])
+            // so ":P" doesn't eat the start of ":Pizza" and the like
+            .filter(|emoticon| !text[index + emoticon.len()..].starts_with(char::is_alphanumeric))
+        {
+            for _ in 1..emoticon.chars().count() {
+                chars.next();
+            }
+            tokens.push(Token {
+                typed: emoticon.to_string(),
+                word: emoticon.to_string(),
+                stem: emoticon.to_string(),
+                ends_clause: false,
+                shouted: false,
+                elongated: false,
+            });
+        } else if is_emoji(c) {
+            let mut emoji = String::from(c);
+            let mut stem = String::from(c);
+            while let Some(&(_, next)) = chars.peek() {
+                if is_emoji_modifier(next) {
+                    emoji.push(next);
+                    chars.next();
+                } else if next == '\u{200d}' {
+                    // zero width joiners glue several emoji into one, like a family
+                    emoji.push(next);
+                    stem.push(next);
+                    chars.next();
+                    if let Some((_, joined)) = chars.next_if(|(_, c)| is_emoji(*c)) {
+                        emoji.push(joined);
+                        stem.push(joined);
+                    }
+                } else {
+                    break;
+                }
+            }
+            // the stem is the emoji without its skin tone, which is what the emoji table has
+            tokens.push(Token { typed: emoji.clone(), word: emoji, stem, ends_clause: false, shouted: false, elongated: false });
+        } else if CLAUSE_PUNCTUATION.contains(&c) {
+            if let Some(last) = tokens.last_mut() {
+                last.ends_clause = true;
+            }
+        }
+    }
+    tokens
+}
+
+fn push_word(tokens: &mut Vec<Token>, stemmer: &Stemmer, typed: &str) {
+    let shouted = typed.chars().filter(|c| c.is_alphabetic()).count() > 1
+        && !typed.chars().any(char::is_lowercase);
+    let typed = typed.to_lowercase();
+    let (squashed, elongated) = squash_elongation(&typed);
+    // chat words are looked up first, "b4" is shorthand for "before" and not leetspeak for "ba"
+    let unleeted = unleet(&squashed);
+    let words = expand(&squashed).or_else(|| expand(&unleeted)).unwrap_or_else(|| vec![unleeted.as_str()]);
+    for (index, word) in words.into_iter().enumerate() {
+        if ARTICLES.contains(&word) {
+            continue;
+        }
+        if let Some(last) = tokens.last_mut() {
+            if word == "of" && !last.ends_clause && (last.word == "kind" || last.word == "sort") {
+                last.typed = format!("{} {}", last.typed, typed);
+                last.word = format!("{} of", last.word);
+                last.stem = last.word.clone();
+                continue;
+            }
+        }
+        tokens.push(Token {
+            typed: typed.clone(),
+            word: word.to_string(),
+            stem: simplify_word(stemmer, word),
+            ends_clause: false,
+            shouted,
+            // "tyyyy" only counts as stretched out once
+            elongated: elongated && index == 0,
+        });
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1f000..=0x1faff   // cards, flags, faces, people, animals, food, symbols
+        | 0x2600..=0x27bf   // the older symbols and dingbats, like hearts and the sun
+        | 0x2b50 | 0x2b55)  // stars and circles
+}
+
+// skin tones, the emoji style selector and flag tags change the emoji before them instead of being their own
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c as u32, 0xfe0f | 0x1f3fb..=0x1f3ff | 0xe0020..=0xe007f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        tokenize(text).into_iter().map(|token| token.word).collect()
+    }
+
+    #[test]
+    fn punctuation_contractions_and_emoji() {
+        assert_eq!(words("\"Nice!\"...you're (really) kind?"), vec!["nice", "you're", "really", "kind"]);
+        assert_eq!(words("I don\u{2019}t 'like' it,bad"), vec!["i", "don't", "like", "it", "bad"]);
+        assert_eq!(words(", ; !!"), Vec::<String>::new());
+        assert_eq!(words("CAFÉ très Ärger"), vec!["café", "très", "ärger"]);
+        assert_eq!(words("love it\u{1f60d}\u{1f60d} \u{1f44d}\u{1f3fd}"), vec!["love", "it", "\u{1f60d}", "\u{1f60d}", "\u{1f44d}\u{1f3fd}"]);
+        assert_eq!(words("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467} well-being"), vec!["\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}", "well-being"]);
+
+        assert_eq!(words("thanks:) <3 :-( http://x.com :Pizza"), vec!["thanks", ":)", "<3", ":-(", "http", "x", "com", "pizza"]);
+        assert_eq!(tokenize("\u{1f44d}\u{1f3fd}")[0].stem, "\u{1f44d}");
+
+        let tokens = tokenize("ÜBER nice, thanks");
+        assert!(tokens[0].shouted);
+        assert!(tokens[1].ends_clause);
+        assert_eq!(tokens[2].stem, "thank");
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use crate::emotion::{Mood, MOOD_DECAY};
+use crate::sentiment::segment::SentenceScore;
+use crate::sentiment::SentimentBreakdown;
+use crate::topic::{Relevance, Topic};
+
+// 0 - start (enemy prompt, wait for player prompt)
+// 1 - after player first response, fetch ai response
+// 2 - after player second response, fetch ai response
+// etc..
+// FINAL TURN - after player final response, return fight or not
+pub const MAX_TURNS: u32 = 4;
+
+// how much of its words a line has to share with an earlier one to count as saying it again
+const REPEAT_OVERLAP: f64 = 0.8;
+// what a nice line is still worth every time it's said again
+const REPEAT_DECAY: f64 = 0.5;
+
+// which list a neighbor's reply comes out of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReplyKind {
+    Nice,
+    Mean,
+    Repeat,     // "you already said that"
+    Huh,        // the neighbor couldn't make out what the player said
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Speaker {
+    Player,
+    Neighbor,
+}
+
+// where the conversation stands after the player's latest line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Ongoing,
+    Won,    // the neighbor is happy, on to the next level
+    Lost,   // the neighbor wants to fight
+}
+
+// what one line from the player did to the neighbor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurnResult {
+    pub score: f64,
+    pub tolerance: f64,
+    pub reply: ReplyKind,
+    pub verdict: Verdict,
+}
+
+// how much a line from the player repeats what they already said this conversation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Repetition {
+    pub times: u32,     // earlier lines it's a near copy of
+    pub overlap: f64,   // the most words it shared with one of them, 0 to 1
+    pub factor: f64,    // what the line's score is multiplied by if it was nice
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptLine {
+    pub speaker: Speaker,
+    pub text: String,
+    pub sentences: Vec<SentenceScore>,  // how each sentence of a player's line scored, if it had more than one
+}
+
+// everything one conversation with a neighbor needs to remember between lines,
+// made fresh for every level so nothing leaks into the next neighbor
+#[derive(Debug, Clone)]
+pub struct ConversationSession {
+    start_tolerance: f64,
+    tolerance: f64,
+    turn: u32,
+    max_turns: u32,
+    used_replies: HashSet<(ReplyKind, usize)>,
+    transcript: Vec<TranscriptLine>,
+    off_topic_streak: u32,
+    said: Vec<HashSet<String>>,     // the simplified words of every line the player said
+    repeated: bool,                 // the line about to be taken was said before
+    mood: Mood,                     // how the neighbor feels, fading a little with every line
+}
+
+impl ConversationSession {
+    pub fn new(start_tolerance: f64, max_turns: u32) -> Self {
+        ConversationSession {
+            start_tolerance,
+            tolerance: start_tolerance,
+            turn: 0,
+            max_turns,
+            used_replies: HashSet::new(),
+            transcript: Vec::new(),
+            off_topic_streak: 0,
+            said: Vec::new(),
+            repeated: false,
+            mood: Mood::default(),
+        }
+    }
+
+    pub fn start_tolerance(&self) -> f64 {
+        self.start_tolerance
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn max_turns(&self) -> u32 {
+        self.max_turns
+    }
+
+    pub fn off_topic_streak(&self) -> u32 {
+        self.off_topic_streak
+    }
+
+    pub fn mood(&self) -> Mood {
+        self.mood
+    }
+
+    // what the player's line made the neighbor feel, on top of what is left from earlier lines
+    pub fn feel(&mut self, line: &Mood) -> Mood {
+        self.mood = self.mood.then(line, MOOD_DECAY);
+        self.mood
+    }
+
+    // how much the player's line stuck to what the neighbor asked about, add its score before taking the turn
+    pub fn weigh_topic(&mut self, topic: &Topic, breakdown: &SentimentBreakdown) -> Relevance {
+        let relevance = topic.relevance(breakdown, self.off_topic_streak);
+        self.off_topic_streak = relevance.off_topic_streak;
+        relevance
+    }
+
+    // counts the player's line, returns false once every turn has been used up
+    pub fn next_turn(&mut self) -> bool {
+        if self.turn <= self.max_turns {
+            self.turn += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // compares the player's simplified line with everything they said before and remembers it,
+    // the first time something is said it counts in full
+    pub fn weigh_repetition(&mut self, words: &[String]) -> Repetition {
+        let words: HashSet<String> = words.iter().cloned().collect();
+        let mut repetition = Repetition { times: 0, overlap: 0., factor: 1. };
+        if words.is_empty() {
+            return repetition;
+        }
+        for earlier in &self.said {
+            let overlap = words.intersection(earlier).count() as f64 / words.union(earlier).count() as f64;
+            repetition.overlap = repetition.overlap.max(overlap);
+            if overlap >= REPEAT_OVERLAP {
+                repetition.times += 1;
+            }
+        }
+        repetition.factor = REPEAT_DECAY.powi(repetition.times as i32);
+        self.repeated = repetition.times > 0;
+        self.said.push(words);
+        repetition
+    }
+
+    // applies the sentiment score of the player's line and decides if the conversation is over
+    pub fn take_turn(&mut self, score: f64) -> TurnResult {
+        self.tolerance += score;
+        let mut verdict = Verdict::Ongoing;
+        let mut player_sent = true;
+        //if the enemy has no more tolerance
+        if self.tolerance <= 0.0 {
+            verdict = Verdict::Lost;
+        } else if self.tolerance >= self.start_tolerance * 2.0 {  //the enemy is so satisfied, the level was won
+            verdict = Verdict::Won;
+        } else {
+            player_sent = score > 0.0;
+        }
+
+        //IF WE ARE NOT OUT OF TURNS, INCREMENT TURNS
+        if !self.next_turn() && verdict == Verdict::Ongoing {
+            //if max turns done, and the tolerance is less than half or player said something mean
+            if self.tolerance <= self.start_tolerance / 2. || !player_sent {
+                verdict = Verdict::Lost;
+            } else {
+                verdict = Verdict::Won;
+            }
+        }
+
+        //a neighbor who has heard it all before says so, unless the conversation just ended
+        let reply = if self.repeated && verdict == Verdict::Ongoing {
+            ReplyKind::Repeat
+        } else if player_sent {
+            ReplyKind::Nice
+        } else {
+            ReplyKind::Mean
+        };
+        self.repeated = false;
+
+        TurnResult {
+            score,
+            tolerance: self.tolerance,
+            reply,
+            verdict,
+        }
+    }
+
+    // a line the neighbor couldn't make out never changes their tolerance,
+    // it's only counted as a turn if the neighbor is too impatient to let it go
+    pub fn take_confused_turn(&mut self, costs_turn: bool) -> TurnResult {
+        let mut turn = if costs_turn {
+            self.take_turn(0.)
+        } else {
+            TurnResult { score: 0., tolerance: self.tolerance, reply: ReplyKind::Huh, verdict: Verdict::Ongoing }
+        };
+        if turn.verdict == Verdict::Ongoing {
+            turn.reply = ReplyKind::Huh;
+        }
+        turn
+    }
+
+    // picks one of `count` replies that hasn't been said yet this conversation,
+    // once they have all been said any of them can come back
+    pub fn pick_reply<R: Rng>(&mut self, kind: ReplyKind, count: usize, rng: &mut R) -> usize {
+        let unused: Vec<usize> = (0..count)
+            .filter(|index| !self.used_replies.contains(&(kind, *index)))
+            .collect();
+        let index = if unused.is_empty() {
+            rng.gen_range(0..count)
+        } else {
+            unused[rng.gen_range(0..unused.len())]
+        };
+        self.used_replies.insert((kind, index));
+        index
+    }
+
+    pub fn record(&mut self, speaker: Speaker, text: &str) {
+        self.record_scored(speaker, text, Vec::new());
+    }
+
+    pub fn record_scored(&mut self, speaker: Speaker, text: &str, sentences: Vec<SentenceScore>) {
+        self.transcript.push(TranscriptLine { speaker, text: text.to_string(), sentences });
+    }
+
+    pub fn transcript(&self) -> &[TranscriptLine] {
+        &self.transcript
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replies_do_not_repeat_and_turns_run_out() {
+        let mut session = ConversationSession::new(10., 2);
+        let mut rng = rand::thread_rng();
+        let mut picked: Vec<usize> = (0..6).map(|_| session.pick_reply(ReplyKind::Nice, 6, &mut rng)).collect();
+        picked.sort();
+        assert_eq!(picked, vec![0, 1, 2, 3, 4, 5]);
+        assert!(session.pick_reply(ReplyKind::Nice, 6, &mut rng) < 6);
+
+        assert!(session.next_turn());
+        assert!(session.next_turn());
+        assert!(session.next_turn());
+        assert!(!session.next_turn());
+        assert_eq!(session.turn(), 3);
+    }
+
+    #[test]
+    fn tolerance_decides_the_conversation() {
+        let mut session = ConversationSession::new(10., MAX_TURNS);
+        assert_eq!(session.take_turn(2.).verdict, Verdict::Ongoing);
+        assert_eq!(session.take_turn(-1.).reply, ReplyKind::Mean);
+        assert_eq!(session.take_turn(9.).verdict, Verdict::Won);
+
+        let mut session = ConversationSession::new(10., MAX_TURNS);
+        assert_eq!(session.take_turn(-10.).verdict, Verdict::Lost);
+
+        // nice enough to get to the end, but the last line was mean
+        let mut session = ConversationSession::new(10., MAX_TURNS);
+        for _ in 0..=MAX_TURNS {
+            assert_eq!(session.take_turn(1.).verdict, Verdict::Ongoing);
+        }
+        let last = session.take_turn(-1.);
+        assert_eq!((last.tolerance, last.verdict), (14., Verdict::Lost));
+    }
+
+    #[test]
+    fn saying_it_again_is_worth_less() {
+        let words = |line: &str| -> Vec<String> { line.split(' ').map(String::from).collect() };
+        let mut session = ConversationSession::new(10., MAX_TURNS);
+        assert_eq!(session.weigh_repetition(&words("you are wonder")).factor, 1.);
+        assert_eq!(session.take_turn(4.).reply, ReplyKind::Nice);
+
+        let again = session.weigh_repetition(&words("you are wonder"));
+        assert_eq!((again.times, again.overlap, again.factor), (1, 1., 0.5));
+        assert_eq!(session.take_turn(2.).reply, ReplyKind::Repeat);
+        assert_eq!(session.weigh_repetition(&words("wonder you are")).factor, 0.25);
+        assert_eq!(session.weigh_repetition(&words("you are great neighbor")).times, 0);
+    }
+
+    #[test]
+    fn mumbling_is_free_unless_the_neighbor_minds() {
+        let mut session = ConversationSession::new(10., MAX_TURNS);
+        let turn = session.take_confused_turn(false);
+        assert_eq!((turn.reply, turn.tolerance, session.turn()), (ReplyKind::Huh, 10., 0));
+        let turn = session.take_confused_turn(true);
+        assert_eq!((turn.reply, turn.tolerance, session.turn()), (ReplyKind::Huh, 10., 1));
+    }
+}
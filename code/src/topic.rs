@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+use crate::sentiment::{mentions, SentimentBreakdown};
+
+// what a neighbor came over about, the "topic" of a neighbor in assets/neighbors.json
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Topic {
+    pub keywords: Vec<String>,  // words or short phrases, simplified the same way the player's lines are
+    #[serde(default = "default_on_topic")]
+    pub on_topic: f64,          // tolerance gained for a line that talks about it
+    #[serde(default = "default_off_topic")]
+    pub off_topic: f64,         // tolerance lost for every line in a row that doesn't
+}
+
+fn default_on_topic() -> f64 {
+    2.
+}
+
+fn default_off_topic() -> f64 {
+    0.5
+}
+
+// how well one line stuck to what the neighbor wanted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relevance {
+    pub mentioned: Vec<String>,     // the keywords the line used
+    pub off_topic_streak: u32,      // lines in a row that ignored the topic, counting this one
+    pub score: f64,                 // added to the line's sentiment score
+}
+
+impl Topic {
+    // ignoring the neighbor costs a little more with every line, so it adds up slowly
+    pub fn relevance(&self, breakdown: &SentimentBreakdown, off_topic_streak: u32) -> Relevance {
+        let mentioned: Vec<String> = self.keywords.iter()
+            .filter(|keyword| mentions(&breakdown.words, keyword))
+            .cloned()
+            .collect();
+        if mentioned.is_empty() {
+            let off_topic_streak = off_topic_streak + 1;
+            Relevance { mentioned, off_topic_streak, score: -self.off_topic * f64::from(off_topic_streak) }
+        } else {
+            Relevance { mentioned, off_topic_streak: 0, score: self.on_topic }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentiment::score_utterance;
+
+    #[test]
+    fn staying_on_topic_pays_and_ignoring_it_adds_up() {
+        let topic = Topic {
+            keywords: vec![String::from("sugar"), String::from("move your car")],
+            on_topic: default_on_topic(),
+            off_topic: default_off_topic(),
+        };
+        let relevance = topic.relevance(&score_utterance("Sure, how much sugar do you need?"), 2);
+        assert_eq!(relevance.mentioned, vec!["sugar"]);
+        assert_eq!((relevance.off_topic_streak, relevance.score), (0, 2.));
+        assert_eq!(topic.relevance(&score_utterance("I'll move my car"), 0).mentioned, Vec::<String>::new());
+        assert_eq!(topic.relevance(&score_utterance("ok, moving your cars"), 0).mentioned, vec!["move your car"]);
+
+        let relevance = topic.relevance(&score_utterance("you are great"), 0);
+        assert_eq!((relevance.off_topic_streak, relevance.score), (1, -0.5));
+        assert_eq!(topic.relevance(&score_utterance("you are great"), 2).score, -1.5);
+    }
+}